@@ -1,150 +1,32 @@
+use day_10::{LoopError, PipeMap};
 use std::time::Instant;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
-impl Direction {
-    fn all() -> Vec<Direction> {
-        vec![
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ]
-    }
-
-    fn matching_dir(&self) -> Direction {
-        match self {
-            Direction::North => Direction::South,
-            Direction::South => Direction::North,
-            Direction::East => Direction::West,
-            Direction::West => Direction::East,
-        }
-    }
-
-    fn to_vec(&self) -> (i64, i64) {
-        match self {
-            Direction::North => (0, -1),
-            Direction::South => (0, 1),
-            Direction::East => (1, 0),
-            Direction::West => (-1, 0),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum PipeElements {
-    Ground,
-    Start,
-    Vertical,
-    Horizontal,
-    BendNE,
-    BendNW,
-    BendSE,
-    BendSW,
-}
-
-impl PipeElements {
-    fn get_directions(&self) -> Vec<Direction> {
-        match self {
-            PipeElements::Ground => vec![],
-            PipeElements::Start => Direction::all(),
-            PipeElements::Vertical => vec![Direction::North, Direction::South],
-            PipeElements::Horizontal => vec![Direction::East, Direction::West],
-            PipeElements::BendNE => vec![Direction::North, Direction::East],
-            PipeElements::BendNW => vec![Direction::North, Direction::West],
-            PipeElements::BendSE => vec![Direction::South, Direction::East],
-            PipeElements::BendSW => vec![Direction::South, Direction::West],
-        }
-    }
-}
-
-impl From<char> for PipeElements {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => PipeElements::Ground,
-            '|' => PipeElements::Vertical,
-            '-' => PipeElements::Horizontal,
-            'S' => PipeElements::Start,
-            'L' => PipeElements::BendNE,
-            'J' => PipeElements::BendNW,
-            'F' => PipeElements::BendSE,
-            '7' => PipeElements::BendSW,
-            _ => panic!("Invalid character"),
-        }
-    }
+/// The tile of the loop furthest from the start and the number of steps to it.
+fn solve(file: &str) -> Result<((usize, usize), usize), LoopError> {
+    PipeMap::parse(file).farthest()
 }
 
-fn solve(file: &str) -> i64 {
-    let mut grid: Vec<Vec<PipeElements>> = file
-        .lines()
-        .map(|line| line.chars().map(|c| PipeElements::from(c)).collect())
-        .collect();
-
-    // Find Starting pos
-    let mut start_pos = (0, 0);
-    for (y, row) in grid.iter().enumerate() {
-        for (x, elem) in row.iter().enumerate() {
-            if *elem == PipeElements::Start {
-                start_pos = (x as i64, y as i64);
-            }
-        }
-    }
-
-    // Loop to starting pos
-    let mut steps = 0;
-    let mut current_pos = start_pos;
-    let mut directions = Direction::all();
-    loop {
-        if current_pos == start_pos && steps > 0 {
-            break;
-        }
-        if directions.len() == 0 {
-            panic!("No directions found");
-        }
-
-        let mut next_dirs: Option<Vec<Direction>> = None;
-        for dir in &directions {
-            let next_pos = (
-                current_pos.0 + dir.to_vec().0,
-                current_pos.1 + dir.to_vec().1,
-            );
-            let next_elem = grid[next_pos.1 as usize][next_pos.0 as usize];
-            let next_dir = next_elem.get_directions();
-            if next_dir.contains(&dir.matching_dir()) {
-                current_pos = next_pos;
-                steps += 1;
-                next_dirs = Some(
-                    next_dir
-                        .iter()
-                        .filter(|d| d != &&dir.matching_dir())
-                        .cloned()
-                        .collect::<Vec<_>>(),
-                );
-                break; // Break out of directions loop
-            }
-        }
-        directions = next_dirs.unwrap();
-    }
-    steps / 2
-}
 fn main() {
     let input = include_str!("input.txt");
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let (farthest, result) = match solve(input) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let duration = t0.elapsed();
     println!("Result: {}", result);
+    println!("Farthest tile: ({}, {})", farthest.0, farthest.1);
     println!("Time: {:?}", duration);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day_10::Direction;
 
     #[test]
     fn test_whole_part() {
@@ -153,6 +35,40 @@ mod tests {
 SJ.L7
 |F--J
 LJ...";
-        assert_eq!(solve(input), 8);
+        assert_eq!(solve(input), Ok(((4, 2), 8)));
+    }
+
+    #[test]
+    fn test_distances() {
+        let input = ".....
+.S-7.
+.|.|.
+.L-J.
+.....";
+        let distances = PipeMap::parse(input).distances().unwrap();
+        assert_eq!(distances[1][1..4], [Some(0), Some(1), Some(2)]);
+        assert_eq!(distances[3][1..4], [Some(2), Some(3), Some(4)]);
+        assert_eq!(distances[2][2], None);
+        assert_eq!(solve(input), Ok(((3, 3), 4)));
+    }
+
+    #[test]
+    fn test_malformed_start() {
+        assert_eq!(solve("...\n.-.\n..."), Err(LoopError::NoStart));
+        assert_eq!(
+            solve("-S-\n.|."),
+            Err(LoopError::StartConnections {
+                connections: vec![Direction::South, Direction::East, Direction::West]
+            })
+        );
+        assert_eq!(
+            solve("S-.\n..."),
+            Err(LoopError::StartConnections {
+                connections: vec![Direction::East]
+            })
+        );
+        // The pipe leaving the start runs off the edge, or into ground, instead of back round
+        assert_eq!(solve("S-7\n|.|"), Err(LoopError::Open { at: (0, 1) }));
+        assert_eq!(solve("S-7\n|.|\nL-."), Err(LoopError::Open { at: (1, 2) }));
     }
 }
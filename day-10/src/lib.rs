@@ -0,0 +1,382 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+impl Direction {
+    pub fn all() -> Vec<Direction> {
+        vec![
+            Direction::North,
+            Direction::South,
+            Direction::East,
+            Direction::West,
+        ]
+    }
+
+    pub fn matching_dir(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
+
+    pub fn to_vec(self) -> (i64, i64) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PipeElements {
+    Ground,
+    Start,
+    Vertical,
+    Horizontal,
+    BendNE,
+    BendNW,
+    BendSE,
+    BendSW,
+    ENCLOSED,
+}
+
+impl fmt::Debug for PipeElements {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipeElements::Ground => write!(f, "."),
+            PipeElements::Start => write!(f, "S"),
+            PipeElements::Vertical => write!(f, "|"),
+            PipeElements::Horizontal => write!(f, "-"),
+            PipeElements::BendNE => write!(f, "L"),
+            PipeElements::BendNW => write!(f, "J"),
+            PipeElements::BendSE => write!(f, "F"),
+            PipeElements::BendSW => write!(f, "7"),
+            PipeElements::ENCLOSED => write!(f, "█"),
+        }
+    }
+}
+
+impl PipeElements {
+    pub fn get_directions(&self) -> Vec<Direction> {
+        match self {
+            PipeElements::Ground => vec![],
+            PipeElements::Start => Direction::all(),
+            PipeElements::Vertical => vec![Direction::North, Direction::South],
+            PipeElements::Horizontal => vec![Direction::East, Direction::West],
+            PipeElements::BendNE => vec![Direction::North, Direction::East],
+            PipeElements::BendNW => vec![Direction::North, Direction::West],
+            PipeElements::BendSE => vec![Direction::South, Direction::East],
+            PipeElements::BendSW => vec![Direction::South, Direction::West],
+            PipeElements::ENCLOSED => vec![],
+        }
+    }
+
+    /// The pipe connecting exactly these two directions.
+    pub fn from_directions(a: Direction, b: Direction) -> Option<PipeElements> {
+        [
+            PipeElements::Vertical,
+            PipeElements::Horizontal,
+            PipeElements::BendNE,
+            PipeElements::BendNW,
+            PipeElements::BendSE,
+            PipeElements::BendSW,
+        ]
+        .into_iter()
+        .find(|p| a != b && p.get_directions().contains(&a) && p.get_directions().contains(&b))
+    }
+
+    /// Box-drawing glyph used when rendering the loop.
+    pub fn glyph(&self) -> char {
+        match self {
+            PipeElements::Ground => ' ',
+            PipeElements::Start => 'S',
+            PipeElements::Vertical => '│',
+            PipeElements::Horizontal => '─',
+            PipeElements::BendNE => '└',
+            PipeElements::BendNW => '┘',
+            PipeElements::BendSE => '┌',
+            PipeElements::BendSW => '┐',
+            PipeElements::ENCLOSED => '█',
+        }
+    }
+}
+
+impl From<char> for PipeElements {
+    fn from(c: char) -> Self {
+        match c {
+            '.' => PipeElements::Ground,
+            '|' => PipeElements::Vertical,
+            '-' => PipeElements::Horizontal,
+            'S' => PipeElements::Start,
+            'L' => PipeElements::BendNE,
+            'J' => PipeElements::BendNW,
+            'F' => PipeElements::BendSE,
+            '7' => PipeElements::BendSW,
+            _ => panic!("Invalid character"),
+        }
+    }
+}
+
+/// Why the start tile is not part of a single closed loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoopError {
+    NoStart,
+    /// The start needs exactly two neighbouring pipes pointing at it to know its shape.
+    StartConnections {
+        connections: Vec<Direction>,
+    },
+    /// Following the loop from the start ran off the grid or into a pipe that does not connect.
+    Open {
+        at: (usize, usize),
+    },
+}
+
+impl fmt::Display for LoopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoopError::NoStart => write!(f, "No start tile in the grid"),
+            LoopError::StartConnections { connections } => write!(
+                f,
+                "Start connects to {} pipes instead of 2: {:?}",
+                connections.len(),
+                connections
+            ),
+            LoopError::Open { at } => write!(
+                f,
+                "Loop from the start is not closed, it breaks at ({}, {})",
+                at.0, at.1
+            ),
+        }
+    }
+}
+
+pub struct PipeMap {
+    pub grid: Vec<Vec<PipeElements>>,
+    pub start_pos: Option<(usize, usize)>,
+}
+
+impl PipeMap {
+    pub fn parse(file: &str) -> Self {
+        let grid: Vec<Vec<PipeElements>> = file
+            .lines()
+            .map(|line| line.chars().map(PipeElements::from).collect())
+            .collect();
+
+        // Find Starting pos
+        let mut start_pos = None;
+        for (y, row) in grid.iter().enumerate() {
+            for (x, elem) in row.iter().enumerate() {
+                if *elem == PipeElements::Start {
+                    start_pos = Some((x, y));
+                }
+            }
+        }
+        PipeMap { grid, start_pos }
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    fn start(&self) -> Result<(usize, usize), LoopError> {
+        self.start_pos.ok_or(LoopError::NoStart)
+    }
+
+    /// The position one step from `pos`, if it is still on the grid.
+    pub fn step(&self, pos: (usize, usize), dir: Direction) -> Option<(usize, usize)> {
+        let x = pos.0.checked_add_signed(dir.to_vec().0 as isize)?;
+        let y = pos.1.checked_add_signed(dir.to_vec().1 as isize)?;
+        (x < self.width() && y < self.height()).then_some((x, y))
+    }
+
+    /// Directions from the start tile towards neighbours that connect back to it.
+    pub fn start_connections(&self) -> Result<Vec<Direction>, LoopError> {
+        let start = self.start()?;
+        Ok(Direction::all()
+            .into_iter()
+            .filter(|dir| {
+                self.step(start, *dir).is_some_and(|(x, y)| {
+                    self.grid[y][x]
+                        .get_directions()
+                        .contains(&dir.matching_dir())
+                })
+            })
+            .collect())
+    }
+
+    /// The pipe hidden under the start tile, worked out from its two connected neighbours.
+    pub fn start_pipe(&self) -> Result<PipeElements, LoopError> {
+        let connections = self.start_connections()?;
+        match connections[..] {
+            [a, b] => Ok(PipeElements::from_directions(a, b).unwrap()),
+            _ => Err(LoopError::StartConnections { connections }),
+        }
+    }
+
+    /// The pipe at `pos`, with the start replaced by its real shape.
+    fn pipe_at(&self, pos: (usize, usize), start_pipe: PipeElements) -> PipeElements {
+        match self.grid[pos.1][pos.0] {
+            PipeElements::Start => start_pipe,
+            pipe => pipe,
+        }
+    }
+
+    /// Every tile of the loop through the start, in walking order beginning at the start.
+    pub fn main_loop(&self) -> Result<Vec<(usize, usize)>, LoopError> {
+        let start = self.start()?;
+        let start_pipe = self.start_pipe()?;
+        let mut path = vec![start];
+        let mut current_pos = start;
+        let mut dir = start_pipe.get_directions()[0];
+        loop {
+            let open = LoopError::Open { at: current_pos };
+            current_pos = self.step(current_pos, dir).ok_or(open.clone())?;
+            if current_pos == start {
+                return Ok(path);
+            }
+            let came_from = dir.matching_dir();
+            let next_dirs = self.grid[current_pos.1][current_pos.0].get_directions();
+            if !next_dirs.contains(&came_from) {
+                return Err(open);
+            }
+            dir = *next_dirs.iter().find(|d| **d != came_from).unwrap();
+            path.push(current_pos);
+        }
+    }
+
+    /// Steps from the start to every tile of the loop along the pipes, `None` off the loop.
+    pub fn distances(&self) -> Result<Vec<Vec<Option<usize>>>, LoopError> {
+        // Walking the loop first makes sure it is closed
+        self.main_loop()?;
+        let start = self.start()?;
+        let start_pipe = self.start_pipe()?;
+
+        let mut distances = vec![vec![None; self.width()]; self.height()];
+        distances[start.1][start.0] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pos) = queue.pop_front() {
+            let dist = distances[pos.1][pos.0].unwrap();
+            for dir in self.pipe_at(pos, start_pipe).get_directions() {
+                let next_pos = self.step(pos, dir).unwrap();
+                if distances[next_pos.1][next_pos.0].is_none() {
+                    distances[next_pos.1][next_pos.0] = Some(dist + 1);
+                    queue.push_back(next_pos);
+                }
+            }
+        }
+        Ok(distances)
+    }
+
+    /// The tile of the loop furthest from the start and its distance.
+    pub fn farthest(&self) -> Result<((usize, usize), usize), LoopError> {
+        let distances = self.distances()?;
+        let mut farthest = (self.start()?, 0);
+        for (y, row) in distances.iter().enumerate() {
+            for (x, dist) in row.iter().enumerate() {
+                if dist.is_some_and(|d| d > farthest.1) {
+                    farthest = ((x, y), dist.unwrap());
+                }
+            }
+        }
+        Ok(farthest)
+    }
+
+    /// A copy of the grid holding only the loop, with the start replaced by its real pipe.
+    pub fn loop_map(&self) -> Result<Vec<Vec<PipeElements>>, LoopError> {
+        let start_pipe = self.start_pipe()?;
+        let mut map = vec![vec![PipeElements::Ground; self.width()]; self.height()];
+        for pos in self.main_loop()? {
+            map[pos.1][pos.0] = self.pipe_at(pos, start_pipe);
+        }
+        Ok(map)
+    }
+}
+
+/// For every tile whether the loop encloses it, by counting loop crossings straight above it.
+/// Odd -> inside, Even -> outside
+///
+/// Walks the whole column for every tile, so `enclosed_scanline` is used instead and this
+/// only checks it.
+pub fn enclosed_upward(loop_map: &[Vec<PipeElements>]) -> Vec<Vec<bool>> {
+    let mut enclosed = vec![vec![false; loop_map[0].len()]; loop_map.len()];
+    for (y, row) in loop_map.iter().enumerate() {
+        for (x, elem) in row.iter().enumerate() {
+            if *elem != PipeElements::Ground {
+                // Part of pipe does not count
+                continue;
+            }
+            // A pipe continuing east and one continuing west together make one crossing
+            let mut connections_a = 0;
+            let mut connections_b = 0;
+            for check_row in loop_map[..y].iter() {
+                let check_dirs = check_row[x].get_directions();
+                if check_dirs.contains(&Direction::East) {
+                    connections_a += 1;
+                }
+                if check_dirs.contains(&Direction::West) {
+                    connections_b += 1;
+                }
+            }
+            enclosed[y][x] = connections_a.min(connections_b) % 2 == 1;
+        }
+    }
+    enclosed
+}
+
+/// Number of enclosed tiles and which ones they are, in a single pass over every row.
+///
+/// Walking a row from the west, the loop is crossed at every tile that connects north
+/// (`|`, `L`, `J`). An `F---J` run crosses once and an `F---7` run not at all, which this
+/// gets right without tracking the run itself.
+pub fn enclosed_scanline(loop_map: &[Vec<PipeElements>]) -> (usize, Vec<Vec<bool>>) {
+    let mut count = 0;
+    let enclosed = loop_map
+        .iter()
+        .map(|row| {
+            let mut inside = false;
+            row.iter()
+                .map(|elem| match elem {
+                    PipeElements::Ground => {
+                        count += inside as usize;
+                        inside
+                    }
+                    pipe => {
+                        inside ^= pipe.get_directions().contains(&Direction::North);
+                        false
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    (count, enclosed)
+}
+
+/// The loop drawn with box-drawing characters, enclosed tiles as `█` and the outside as `·`.
+pub fn render(loop_map: &[Vec<PipeElements>], enclosed: &[Vec<bool>]) -> String {
+    let mut out = String::new();
+    for (row, enclosed_row) in loop_map.iter().zip(enclosed.iter()) {
+        for (elem, inside) in row.iter().zip(enclosed_row.iter()) {
+            out.push(match (elem, inside) {
+                (PipeElements::Ground, true) => PipeElements::ENCLOSED.glyph(),
+                (PipeElements::Ground, false) => '·',
+                (pipe, _) => pipe.glyph(),
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
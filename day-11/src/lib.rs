@@ -0,0 +1,151 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Space {
+    Empty,
+    Galaxy,
+}
+impl From<char> for Space {
+    fn from(c: char) -> Self {
+        match c {
+            '.' => Space::Empty,
+            '#' => Space::Galaxy,
+            _ => panic!("Invalid space"),
+        }
+    }
+}
+
+/// The galaxies as observed, and how many empty rows and columns lie before each position.
+pub struct Universe {
+    galaxies: Vec<(usize, usize)>,
+    /// `empty_cols_before[x]` is the number of empty columns left of column `x`.
+    empty_cols_before: Vec<u64>,
+    /// `empty_rows_before[y]` is the number of empty rows above row `y`.
+    empty_rows_before: Vec<u64>,
+}
+
+/// Running count of `true` before every index.
+fn prefix_counts(empty: &[bool]) -> Vec<u64> {
+    let mut counts = vec![0; empty.len() + 1];
+    for (i, e) in empty.iter().enumerate() {
+        counts[i + 1] = counts[i] + *e as u64;
+    }
+    counts
+}
+
+/// Sum of `|a - b|` over all pairs, from the sorted values in O(n log n).
+fn pairwise_sum(mut values: Vec<u64>) -> u64 {
+    values.sort_unstable();
+    // Every value is the larger one of a pair with all values before it
+    let mut total = 0;
+    let mut before = 0;
+    for (i, v) in values.iter().enumerate() {
+        total += v * i as u64 - before;
+        before += v;
+    }
+    total
+}
+
+impl Universe {
+    pub fn parse(file: &str) -> Self {
+        let space = file
+            .lines()
+            .map(|l| l.chars().map(Space::from).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let galaxies = space
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, s)| **s == Space::Galaxy)
+                    .map(move |(x, _)| (x, y))
+            })
+            .collect::<Vec<_>>();
+
+        // A row or column is empty when no galaxy lies in it
+        let mut empty_cols = vec![true; space[0].len()];
+        let mut empty_rows = vec![true; space.len()];
+        for (x, y) in galaxies.iter() {
+            empty_cols[*x] = false;
+            empty_rows[*y] = false;
+        }
+
+        Universe {
+            galaxies,
+            empty_cols_before: prefix_counts(&empty_cols),
+            empty_rows_before: prefix_counts(&empty_rows),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.galaxies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.galaxies.is_empty()
+    }
+
+    /// Where galaxy `i` ends up once every empty row and column is `expansion` times as wide.
+    pub fn expanded(&self, i: usize, expansion: u64) -> (u64, u64) {
+        let (x, y) = self.galaxies[i];
+        (
+            x as u64 + self.empty_cols_before[x] * (expansion - 1),
+            y as u64 + self.empty_rows_before[y] * (expansion - 1),
+        )
+    }
+
+    /// Shortest path between two galaxies, numbered from 1 in reading order like the puzzle.
+    pub fn distance(&self, a: usize, b: usize, expansion: u64) -> Option<u64> {
+        if a == 0 || b == 0 || a.max(b) > self.len() {
+            return None;
+        }
+        let (x1, y1) = self.expanded(a - 1, expansion);
+        let (x2, y2) = self.expanded(b - 1, expansion);
+        Some(x1.abs_diff(x2) + y1.abs_diff(y2))
+    }
+
+    /// Sum of the shortest paths between every pair of galaxies.
+    pub fn total_distance(&self, expansion: u64) -> u64 {
+        // Manhattan distance splits into the two axes, which are summed separately
+        let (xs, ys) = (0..self.len()).map(|i| self.expanded(i, expansion)).unzip();
+        pairwise_sum(xs) + pairwise_sum(ys)
+    }
+}
+
+/// The expansion factor from `--expansion`, or `default` without one.
+pub fn expansion_from_args(default: u64) -> u64 {
+    let args = std::env::args().collect::<Vec<_>>();
+    args.iter()
+        .position(|a| a == "--expansion")
+        .and_then(|i| args.get(i + 1))
+        .map(|n| match n.parse() {
+            Ok(n) if n > 0 => n,
+            _ => panic!("Expansion must be a positive number: {}", n),
+        })
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairwise_sum() {
+        let values: Vec<u64> = vec![7, 1, 4, 4, 10];
+        let brute = values
+            .iter()
+            .flat_map(|a| values.iter().map(move |b| a.abs_diff(*b)))
+            .sum::<u64>()
+            / 2;
+        assert_eq!(pairwise_sum(values), brute);
+        assert_eq!(pairwise_sum(vec![]), 0);
+    }
+
+    #[test]
+    fn test_prefix_counts() {
+        assert_eq!(
+            prefix_counts(&[false, true, true, false, true]),
+            vec![0, 0, 1, 2, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use day_12::{arg_value, fold_arg, Record, RecordError, XorShift};
+use std::time::Instant;
+
+/// Every record with its count, the first `list` arrangements and, given a seed, a random one.
+fn describe(
+    file: &str,
+    fold: usize,
+    list: usize,
+    seed: Option<u64>,
+) -> Result<String, RecordError> {
+    let mut rng = seed.map(XorShift::new);
+    let mut out = String::new();
+    for line in file.lines() {
+        let record = Record::parse(line, fold)?;
+        let count = record.count();
+        let plural = if count == 1 { "" } else { "s" };
+        out += &format!("{}: {} arrangement{}\n", line, count, plural);
+        for arrangement in record.arrangements().take(list) {
+            out += &format!("  {}\n", arrangement);
+        }
+        if let Some(sample) = rng.as_mut().and_then(|rng| record.random_arrangement(rng)) {
+            out += &format!("  random: {}\n", sample);
+        }
+    }
+    Ok(out)
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+    let fold = match fold_arg(1) {
+        Ok(fold) => fold,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let list = arg_value("--list", 3) as usize;
+    let seed = std::env::args()
+        .any(|a| a == "--seed")
+        .then(|| arg_value("--seed", 0));
+
+    let t0 = Instant::now();
+    match describe(input, fold, list, seed) {
+        Ok(out) => print!("{}", out),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    println!("Time: {:?}", t0.elapsed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let input = "???.### 1,1,3\n.??..??...?##. 1,1,3";
+        assert_eq!(
+            describe(input, 1, 2, None).unwrap(),
+            "???.### 1,1,3: 1 arrangement
+  #.#.###
+.??..??...?##. 1,1,3: 4 arrangements
+  .#...#....###.
+  .#....#...###.
+"
+        );
+        let sampled = describe(input, 1, 0, Some(3)).unwrap();
+        assert!(sampled.contains("  random: #.#.###\n"));
+        assert_eq!(sampled.lines().count(), 4);
+        assert_eq!(describe(input, 0, 2, None), Err(RecordError::ZeroFold));
+    }
+}
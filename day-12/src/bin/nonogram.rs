@@ -0,0 +1,99 @@
+use day_12::{
+    arg_value,
+    nonogram::{render, Nonogram},
+};
+use std::time::Instant;
+
+/// Every solution found, up to `limit`, followed by how many there are.
+fn describe(file: &str, limit: usize) -> String {
+    if limit == 0 {
+        return "Not looking for any solutions\n".to_string();
+    }
+    let solutions = Nonogram::parse(file).solve(limit);
+    let mut out = String::new();
+    for (i, picture) in solutions.iter().enumerate() {
+        out += &format!("Solution {}:\n{}", i + 1, render(picture));
+    }
+    out += &match solutions.len() {
+        0 => "No solution\n".to_string(),
+        // With a limit of 1 there is no telling whether another one exists
+        1 if limit == 1 => "Stopped looking after 1 solution\n".to_string(),
+        1 => "Unique solution\n".to_string(),
+        n if n >= limit => format!("Multiple solutions, stopped looking after {} of them\n", n),
+        n => format!("Multiple solutions, {} in total\n", n),
+    };
+    out
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let Some(file) = args
+        .iter()
+        .position(|a| a == "--puzzle")
+        .and_then(|i| args.get(i + 1))
+    else {
+        eprintln!("Usage: nonogram --puzzle <file> [--max <solutions>]");
+        std::process::exit(1);
+    };
+    let input = std::fs::read_to_string(file).unwrap();
+    let limit = arg_value("--max", 2) as usize;
+
+    let t0 = Instant::now();
+    print!("{}", describe(&input, limit));
+    println!("Time: {:?}", t0.elapsed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique() {
+        // A small arrow, rows then columns
+        let input = "1
+3
+5
+1
+1
+
+1
+2
+5
+2
+1";
+        assert_eq!(
+            describe(input, 2),
+            "Solution 1:
+..#..
+.###.
+#####
+..#..
+..#..
+Unique solution
+"
+        );
+    }
+
+    #[test]
+    fn test_needs_guessing() {
+        // Line by line nothing is forced, only the diagonals are possible
+        let input = "1\n1\n\n1\n1";
+        assert_eq!(
+            describe(input, 5),
+            "Solution 1:
+#.
+.#
+Solution 2:
+.#
+#.
+Multiple solutions, 2 in total
+"
+        );
+        assert!(
+            describe(input, 2).ends_with("Multiple solutions, stopped looking after 2 of them\n")
+        );
+        assert!(describe(input, 1).ends_with("Stopped looking after 1 solution\n"));
+        assert_eq!(describe(input, 0), "Not looking for any solutions\n");
+        assert_eq!(describe("2\n0\n\n1\n0", 2), "No solution\n");
+    }
+}
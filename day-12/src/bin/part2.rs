@@ -1,3 +1,4 @@
+use day_12::fold_arg;
 use std::time::Instant;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -19,15 +20,12 @@ impl From<char> for SpringStatus {
 }
 
 fn recurive_group_reduction(
-    possible_groups: &Vec<Vec<SpringStatus>>,
-    target_group_lengths: &Vec<i64>,
+    possible_groups: &[Vec<SpringStatus>],
+    target_group_lengths: &[i64],
 ) -> i64 {
     // return the number of possible groups
 
-    println!("Possible groups: {:?}", possible_groups);
-    println!("Target group lengths: {:?}", target_group_lengths);
-
-    if target_group_lengths.len() == 0 {
+    if target_group_lengths.is_empty() {
         let contains_bad = possible_groups
             .iter()
             .any(|g| g.iter().any(|s| s == &SpringStatus::Bad));
@@ -36,7 +34,7 @@ fn recurive_group_reduction(
         }
         return 1;
     }
-    if possible_groups.len() == 0 {
+    if possible_groups.is_empty() {
         return 0;
     }
 
@@ -44,27 +42,22 @@ fn recurive_group_reduction(
     let first_len = target_group_lengths.first().unwrap();
     // Remove first and last groups that do not fit
     if first_len > &(first_group.len() as i64) {
-        return recurive_group_reduction(&possible_groups[1..].to_vec(), &target_group_lengths);
+        return recurive_group_reduction(&possible_groups[1..], target_group_lengths);
     }
     if target_group_lengths.last().unwrap() > &(possible_groups.last().unwrap().len() as i64) {
         return recurive_group_reduction(
-            &possible_groups[..possible_groups.len() - 1].to_vec(),
-            &target_group_lengths,
+            &possible_groups[..possible_groups.len() - 1],
+            target_group_lengths,
         );
     }
 
     // try first group on first possible
     let mut options = 0;
     if first_group.len() == *first_len as usize {
-        options += recurive_group_reduction(
-            &possible_groups[1..].to_vec(),
-            &target_group_lengths[1..].to_vec(),
-        );
+        options += recurive_group_reduction(&possible_groups[1..], &target_group_lengths[1..]);
     } else if first_group.len() == *first_len as usize + 1 {
-        let new_options = recurive_group_reduction(
-            &possible_groups[1..].to_vec(),
-            &target_group_lengths[1..].to_vec(),
-        );
+        let new_options =
+            recurive_group_reduction(&possible_groups[1..], &target_group_lengths[1..]);
 
         if first_group.first().unwrap() == &SpringStatus::Either
             && possible_groups.first().unwrap().last().unwrap() == &SpringStatus::Either
@@ -88,31 +81,22 @@ fn recurive_group_reduction(
             let mut new_possible_groups = possible_groups[1..].to_vec();
             if end_ind < first_group.len() {
                 let new_first_group = first_group[(end_ind + 1)..].to_vec();
-                // println!("new_first_group: {:?}", new_first_group);
                 new_possible_groups.insert(0, new_first_group);
             }
-            options +=
-                recurive_group_reduction(&new_possible_groups, &target_group_lengths[1..].to_vec());
+            options += recurive_group_reduction(&new_possible_groups, &target_group_lengths[1..]);
 
             if first_group[i] == SpringStatus::Bad {
-                println!("Possible groups: {:?}", possible_groups);
-                println!("Target group lengths: {:?}", target_group_lengths);
-                println!("BREAKING");
                 break;
             }
         }
     }
     if !first_group.contains(&SpringStatus::Bad) {
-        options += recurive_group_reduction(
-            &possible_groups[1..].to_vec(),
-            &target_group_lengths.to_vec(),
-        );
+        options += recurive_group_reduction(&possible_groups[1..], target_group_lengths);
     }
-    println!("Options: {}", options);
     options
 }
 
-fn solve(file: &str) -> i64 {
+fn solve(file: &str, fold: usize) -> i64 {
     file.lines()
         .map(|line| {
             let mut parts = line.split_whitespace();
@@ -120,7 +104,7 @@ fn solve(file: &str) -> i64 {
                 .next()
                 .unwrap()
                 .chars()
-                .map(|c| SpringStatus::from(c))
+                .map(SpringStatus::from)
                 .collect::<Vec<_>>();
 
             let groups = parts
@@ -130,27 +114,21 @@ fn solve(file: &str) -> i64 {
                 .map(|s| s.parse::<i64>().unwrap())
                 .collect::<Vec<_>>();
 
-            // Expand by repeating 5 times
-            // let groups = groups
-            //     .iter()
-            //     .cycle()
-            //     .take(groups.len() * 5)
-            //     .cloned()
-            //     .collect::<Vec<_>>();
-            //
-            // let mut expanded_map = vec![];
-            // for i in 0..5 {
-            //     if i != 0 {
-            //         expanded_map.push(SpringStatus::Either);
-            //     }
-            //     expanded_map.extend(map.iter());
-            // }
-            //
+            // Expand by repeating `fold` times
+            let groups = groups.repeat(fold);
+            let mut expanded_map = vec![];
+            for i in 0..fold {
+                if i != 0 {
+                    expanded_map.push(SpringStatus::Either);
+                }
+                expanded_map.extend(map.iter());
+            }
+
             let mut inner_groups = vec![vec![]];
-            for s in map.iter() {
+            for s in expanded_map.iter() {
                 match s {
                     SpringStatus::Good => {
-                        if inner_groups.last().unwrap().len() > 0 {
+                        if !inner_groups.last().unwrap().is_empty() {
                             inner_groups.push(vec![]);
                         }
                     }
@@ -163,18 +141,23 @@ fn solve(file: &str) -> i64 {
                 }
             }
 
-            let num = recurive_group_reduction(&inner_groups, &groups);
-            println!("{} \t\tnum: {}", line, num);
-            num
+            recurive_group_reduction(&inner_groups, &groups)
         })
         .sum()
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let fold = match fold_arg(5) {
+        Ok(fold) => fold,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = solve(input, fold);
     let duration = t0.elapsed();
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
@@ -184,7 +167,7 @@ fn main() {
 mod tests {
     use super::*;
 
-    // #[test]
+    #[test]
     fn test_whole_part() {
         let input = "???.### 1,1,3
 .??..??...?##. 1,1,3
@@ -192,12 +175,13 @@ mod tests {
 ????.#...#... 4,1,1
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
-        assert_eq!(solve(input), 525152);
+        assert_eq!(solve(input, 1), 21);
+        assert_eq!(solve(input, 5), 525152);
     }
 
     #[test]
     fn test_whole_part2() {
         let input = "????##??#.#?? 1,2,1,1";
-        assert_eq!(solve(input), 3);
+        assert_eq!(solve(input, 1), 3);
     }
 }
@@ -1,4 +1,5 @@
-use std::{collections::HashMap, time::Instant};
+use day_12::fold_arg;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 enum State {
@@ -24,18 +25,18 @@ struct Case {
     groups: Vec<Vec<State>>,
 }
 
-impl From<&str> for Case {
-    fn from(s: &str) -> Self {
+impl Case {
+    fn parse(s: &str, fold: usize) -> Self {
         let mut parts = s.split_whitespace();
         let single_row = parts
             .next()
             .unwrap()
             .chars()
-            .map(|c| State::from(c))
+            .map(State::from)
             .collect::<Vec<State>>();
 
         let mut row = single_row.clone();
-        for _ in 0..4 {
+        for _ in 1..fold {
             row.push(State::Maybe);
             row.extend(single_row.clone());
         }
@@ -55,7 +56,7 @@ impl From<&str> for Case {
         let length = length
             .iter()
             .cycle()
-            .take(length.len() * 5)
+            .take(length.len() * fold)
             .cloned()
             .collect();
 
@@ -74,18 +75,17 @@ impl Case {
         count += self._find_solutions(cache);
 
         cache.insert(self.clone(), count);
-        // println!("{:?} {}", self, count);
         count
     }
 
     fn _trivial_solutions(&self) -> Option<i64> {
-        if self.length.len() == 0 {
+        if self.length.is_empty() {
             if self.groups.iter().any(|g| g.contains(&State::Yes)) {
                 return Some(0);
             }
             return Some(1);
         }
-        if self.groups.len() == 0 {
+        if self.groups.is_empty() {
             return Some(0);
         }
         if self.length.iter().sum::<i64>() > self.groups.iter().map(|g| g.len() as i64).sum::<i64>()
@@ -146,12 +146,11 @@ impl Case {
         // use the entire first group
         if first_group.len() <= *self.length.first().unwrap() as usize + 1 {
             let mut multiplier = 1;
-            if first_group.len() == *self.length.first().unwrap() as usize + 1 {
-                if first_group.last().unwrap() == &State::Maybe
-                    && first_group.first().unwrap() == &State::Maybe
-                {
-                    multiplier = 2;
-                }
+            if first_group.len() == *self.length.first().unwrap() as usize + 1
+                && first_group.last().unwrap() == &State::Maybe
+                && first_group.first().unwrap() == &State::Maybe
+            {
+                multiplier = 2;
             }
             let part_options = Case {
                 length: self.length[1..].to_vec(),
@@ -165,18 +164,15 @@ impl Case {
             let len = *self.length.first().unwrap() as usize;
             for i in 0..=(first_group.len() - len) {
                 let end_ind = i + len;
-                match first_group.iter().nth(end_ind) {
-                    Some(State::Yes) => {
-                        if first_group[i] == State::Yes {
-                            break;
-                        }
-                        continue;
+                if let Some(State::Yes) = first_group.get(end_ind) {
+                    if first_group[i] == State::Yes {
+                        break;
                     }
-                    _ => (),
+                    continue;
                 }
 
                 let mut new_groups = self.groups[1..].to_vec();
-                if end_ind + 1 <= first_group.len() - 1 {
+                if end_ind < first_group.len() - 1 {
                     new_groups.insert(0, first_group[end_ind + 1..].to_vec());
                 }
                 options += Case {
@@ -195,28 +191,31 @@ impl Case {
     }
 }
 
-fn solve(file: &str) -> i64 {
-    let cases = file.lines().map(|l| Case::from(l)).collect::<Vec<_>>();
+fn solve(file: &str, fold: usize) -> i64 {
+    let cases = file
+        .lines()
+        .map(|l| Case::parse(l, fold))
+        .collect::<Vec<_>>();
 
     let mut cache = HashMap::new();
 
     cases
         .iter()
         .map(|c| c.solution_count(&mut cache))
-        .enumerate()
-        .inspect(|(i, c)| println!("{} {}", i, c))
-        .map(|(_, c)| c)
         .sum::<i64>()
 }
 
 fn main() {
     let input = include_str!("input.txt");
-    println!("Starting solution");
-    let t0 = Instant::now();
-    let result = solve(input);
-    let duration = t0.elapsed();
+    let fold = match fold_arg(5) {
+        Ok(fold) => fold,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let result = solve(input, fold);
     println!("Result: {}", result);
-    println!("Time: {:?}", duration);
 }
 
 #[cfg(test)]
@@ -231,7 +230,8 @@ mod tests {
 ????.#...#... 4,1,1
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
-        assert_eq!(solve(input), 21);
+        assert_eq!(solve(input, 1), 21);
+        assert_eq!(solve(input, 5), 525152);
     }
 
     // #[test]
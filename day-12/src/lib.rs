@@ -0,0 +1,347 @@
+use std::fmt;
+
+pub mod nonogram;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum State {
+    No,
+    Yes,
+    Maybe,
+}
+
+impl From<char> for State {
+    fn from(c: char) -> Self {
+        match c {
+            '.' => State::No,
+            '#' => State::Yes,
+            '?' => State::Maybe,
+            _ => panic!("Invalid char"),
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::No => write!(f, "."),
+            State::Yes => write!(f, "#"),
+            State::Maybe => write!(f, "?"),
+        }
+    }
+}
+
+/// Whether group `g` can start at spring `i`, and where the rest continues after it.
+pub(crate) fn place(springs: &[State], groups: &[usize], i: usize, g: usize) -> Option<usize> {
+    let end = i + *groups.get(g)?;
+    if end > springs.len() || springs[i..end].contains(&State::No) {
+        return None;
+    }
+    match springs.get(end) {
+        None => Some(end),
+        Some(State::Yes) => None,
+        // The spring after a group has to be operational
+        Some(_) => Some(end + 1),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    /// A record has to be unfolded at least once to have any springs.
+    ZeroFold,
+    /// The arrangements do not fit in a `u64`.
+    TooManyArrangements,
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::ZeroFold => write!(f, "Records have to be folded at least once"),
+            RecordError::TooManyArrangements => {
+                write!(f, "More arrangements than fit in 64 bits")
+            }
+        }
+    }
+}
+
+/// One row of the condition records, unfolded `fold` times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub springs: Vec<State>,
+    pub groups: Vec<usize>,
+    /// `ways[i][g]` is the number of arrangements of `groups[g..]` in `springs[i..]`.
+    ways: Vec<Vec<u64>>,
+}
+
+impl Record {
+    pub fn new(springs: Vec<State>, groups: Vec<usize>) -> Result<Self, RecordError> {
+        let mut record = Record {
+            springs,
+            groups,
+            ways: vec![],
+        };
+        record.ways = record.count_ways()?;
+        Ok(record)
+    }
+
+    /// Parses `???.### 1,1,3`, repeating the springs `fold` times joined by `?` and the
+    /// groups `fold` times.
+    pub fn parse(line: &str, fold: usize) -> Result<Self, RecordError> {
+        if fold < 1 {
+            return Err(RecordError::ZeroFold);
+        }
+        let mut parts = line.split_whitespace();
+        let single_row = parts
+            .next()
+            .unwrap()
+            .chars()
+            .map(State::from)
+            .collect::<Vec<State>>();
+        let single_groups = parts
+            .next()
+            .unwrap()
+            .split(',')
+            .map(|p| p.parse::<usize>().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut springs = single_row.clone();
+        for _ in 1..fold {
+            springs.push(State::Maybe);
+            springs.extend(single_row.iter());
+        }
+        let groups = single_groups.repeat(fold);
+        Record::new(springs, groups)
+    }
+
+    fn count_ways(&self) -> Result<Vec<Vec<u64>>, RecordError> {
+        let n = self.springs.len();
+        let m = self.groups.len();
+        let mut ways = vec![vec![0; m + 1]; n + 1];
+        ways[n][m] = 1;
+        for i in (0..n).rev() {
+            for g in 0..=m {
+                let mut count = 0_u64;
+                if self.springs[i] != State::Yes {
+                    count = ways[i + 1][g];
+                }
+                if let Some(next) = place(&self.springs, &self.groups, i, g) {
+                    count = count
+                        .checked_add(ways[next][g + 1])
+                        .ok_or(RecordError::TooManyArrangements)?;
+                }
+                ways[i][g] = count;
+            }
+        }
+        Ok(ways)
+    }
+
+    /// Number of ways to fill in the unknown springs.
+    pub fn count(&self) -> u64 {
+        self.ways[0][0]
+    }
+
+    /// The arrangement at `index` when all of them are sorted as strings, so `#` before `.`.
+    pub fn arrangement(&self, mut index: u64) -> Option<String> {
+        if index >= self.count() {
+            return None;
+        }
+        let mut out = String::new();
+        let (mut i, mut g) = (0, 0);
+        while i < self.springs.len() {
+            // Starting the next group here sorts before leaving this spring operational
+            if let Some(next) = place(&self.springs, &self.groups, i, g) {
+                let with_group = self.ways[next][g + 1];
+                if index < with_group {
+                    out.push_str(&"#".repeat(self.groups[g]));
+                    if next > i + self.groups[g] {
+                        out.push('.');
+                    }
+                    (i, g) = (next, g + 1);
+                    continue;
+                }
+                index -= with_group;
+            }
+            out.push('.');
+            i += 1;
+        }
+        Some(out)
+    }
+
+    /// Every arrangement in sorted order, each one only worked out when it is reached.
+    pub fn arrangements(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.count()).map(|i| self.arrangement(i).unwrap())
+    }
+
+    /// An arrangement picked uniformly at random from all of them.
+    pub fn random_arrangement(&self, rng: &mut XorShift) -> Option<String> {
+        match self.count() {
+            0 => None,
+            count => self.arrangement(rng.below(count)),
+        }
+    }
+}
+
+/// Small xorshift generator, so samples can be repeated from a seed.
+pub struct XorShift(u64);
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        // Zero would stay zero forever
+        XorShift(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `0..n`, redrawing the values that would favour small results.
+    pub fn below(&mut self, n: u64) -> u64 {
+        let limit = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < limit {
+                return x % n;
+            }
+        }
+    }
+}
+
+/// Arrangements over all records, unfolded `fold` times.
+pub fn total_arrangements(file: &str, fold: usize) -> Result<u64, RecordError> {
+    file.lines().try_fold(0_u64, |total, line| {
+        total
+            .checked_add(Record::parse(line, fold)?.count())
+            .ok_or(RecordError::TooManyArrangements)
+    })
+}
+
+/// `--fold`, or `default` without it, which has to be at least 1.
+pub fn fold_arg(default: u64) -> Result<usize, RecordError> {
+    match arg_value("--fold", default) {
+        0 => Err(RecordError::ZeroFold),
+        fold => Ok(fold as usize),
+    }
+}
+
+/// A numeric flag such as `--fold 5`, or `default` without one.
+pub fn arg_value(flag: &str, default: u64) -> u64 {
+    let args = std::env::args().collect::<Vec<_>>();
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(|n| n.parse().unwrap())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every way to fill in the `?`, in sorted order, by trying them all.
+    fn brute_force(record: &Record) -> Vec<String> {
+        let unknown = record
+            .springs
+            .iter()
+            .filter(|s| **s == State::Maybe)
+            .count();
+        let mut found = (0..1_u32 << unknown)
+            .map(|bits| {
+                let mut bit = 0;
+                record
+                    .springs
+                    .iter()
+                    .map(|s| match s {
+                        State::Maybe => {
+                            bit += 1;
+                            match bits >> (bit - 1) & 1 {
+                                1 => '#',
+                                _ => '.',
+                            }
+                        }
+                        s => s.to_string().chars().next().unwrap(),
+                    })
+                    .collect::<String>()
+            })
+            .filter(|row| {
+                row.split('.')
+                    .filter(|g| !g.is_empty())
+                    .map(|g| g.len())
+                    .collect::<Vec<_>>()
+                    == record.groups
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        found
+    }
+
+    const INPUT: &str = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+?.???...?? 1,1,1
+##?. 3
+.. 1";
+
+    #[test]
+    fn test_arrangements_match_brute_force() {
+        for line in INPUT.lines() {
+            for fold in 1..=2 {
+                let record = Record::parse(line, fold).unwrap();
+                let expected = brute_force(&record);
+                assert_eq!(record.count(), expected.len() as u64, "{}", line);
+                assert_eq!(record.arrangements().collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arrangement() {
+        let record = Record::parse("?###???????? 3,2,1", 1).unwrap();
+        assert_eq!(record.count(), 10);
+        assert_eq!(record.arrangement(0).unwrap(), ".###.##.#...");
+        assert_eq!(record.arrangement(9).unwrap(), ".###....##.#");
+        assert_eq!(record.arrangement(10), None);
+        assert_eq!(
+            Record::parse("?###???????? 3,2,1", 5).unwrap().count(),
+            506250
+        );
+    }
+
+    #[test]
+    fn test_fold_limits() {
+        assert_eq!(Record::parse("? 1", 0), Err(RecordError::ZeroFold));
+        // Over 2^64 ways to place the groups among all the unknown springs
+        assert_eq!(
+            Record::parse("??????????????? 1", 40),
+            Err(RecordError::TooManyArrangements)
+        );
+        assert_eq!(
+            total_arrangements("???.### 1,1,3\n.??..??...?##. 1,1,3", 5),
+            Ok(1 + 16384)
+        );
+    }
+
+    #[test]
+    fn test_random_arrangement() {
+        let record = Record::parse("?###???????? 3,2,1", 1).unwrap();
+        let all = record.arrangements().collect::<Vec<_>>();
+        let mut seen = vec![0; all.len()];
+        let mut rng = XorShift::new(7);
+        for _ in 0..10_000 {
+            let sample = record.random_arrangement(&mut rng).unwrap();
+            seen[all.iter().position(|a| *a == sample).unwrap()] += 1;
+        }
+        // Roughly 1000 each
+        assert!(seen.iter().all(|n| (800..1200).contains(n)), "{:?}", seen);
+        assert_eq!(
+            Record::parse(".. 1", 1)
+                .unwrap()
+                .random_arrangement(&mut rng),
+            None
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{place, State};
+
+/// For every cell of a line whether it can be filled and whether it can be empty, over all
+/// arrangements of `groups` consistent with what is known. `None` if there are none.
+///
+/// The same walk as `Record::count_ways`, only keeping whether a state can be reached from the
+/// start and whether the rest can still be finished from it.
+pub fn line_options(cells: &[State], groups: &[usize]) -> Option<Vec<(bool, bool)>> {
+    let n = cells.len();
+    let m = groups.len();
+
+    // finishes[i][g]: groups[g..] fit in cells[i..]
+    let mut finishes = vec![vec![false; m + 1]; n + 1];
+    finishes[n][m] = true;
+    for i in (0..n).rev() {
+        for g in 0..=m {
+            finishes[i][g] = (cells[i] != State::Yes && finishes[i + 1][g])
+                || place(cells, groups, i, g).is_some_and(|next| finishes[next][g + 1]);
+        }
+    }
+    if !finishes[0][0] {
+        return None;
+    }
+
+    // Walk forwards through the states that can both be reached and finished
+    let mut reached = vec![vec![false; m + 1]; n + 1];
+    reached[0][0] = true;
+    let mut options = vec![(false, false); n];
+    for i in 0..n {
+        for g in 0..=m {
+            if !reached[i][g] {
+                continue;
+            }
+            if cells[i] != State::Yes && finishes[i + 1][g] {
+                options[i].1 = true;
+                reached[i + 1][g] = true;
+            }
+            if let Some(next) = place(cells, groups, i, g).filter(|next| finishes[*next][g + 1]) {
+                let end = i + groups[g];
+                options[i..end].iter_mut().for_each(|o| o.0 = true);
+                if next > end {
+                    options[end].1 = true;
+                }
+                reached[next][g + 1] = true;
+            }
+        }
+    }
+    Some(options)
+}
+
+/// Fills in every cell of a line that has the same state in all arrangements. Returns whether
+/// anything changed, or `None` when the line can not be completed.
+fn settle_line(cells: &mut [State], groups: &[usize]) -> Option<bool> {
+    let options = line_options(cells, groups)?;
+    let mut changed = false;
+    for (cell, options) in cells.iter_mut().zip(options) {
+        let forced = match options {
+            (true, false) => State::Yes,
+            (false, true) => State::No,
+            _ => continue,
+        };
+        if *cell == State::Maybe {
+            *cell = forced;
+            changed = true;
+        }
+    }
+    Some(changed)
+}
+
+/// A picture puzzle: the same clues as the springs, for every row and every column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<usize>>,
+    pub cols: Vec<Vec<usize>>,
+}
+
+pub type Picture = Vec<Vec<State>>;
+
+impl Nonogram {
+    /// Row clues one per line, a blank line, then column clues. `0` is an empty line.
+    pub fn parse(file: &str) -> Self {
+        let mut sections = file.trim().split("\n\n").map(|section| {
+            section
+                .lines()
+                .map(|l| {
+                    l.trim()
+                        .split(',')
+                        .map(|n| n.trim().parse::<usize>().unwrap())
+                        .filter(|n| *n > 0)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        });
+        let rows = sections.next().unwrap();
+        let cols = sections.next().expect("Missing column clues");
+        Nonogram { rows, cols }
+    }
+
+    /// Settles rows and columns until nothing changes. `false` if the picture is impossible.
+    fn propagate(&self, picture: &mut Picture) -> bool {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (row, groups) in picture.iter_mut().zip(self.rows.iter()) {
+                match settle_line(row, groups) {
+                    Some(c) => changed |= c,
+                    None => return false,
+                }
+            }
+            for (x, groups) in self.cols.iter().enumerate() {
+                let mut col = picture.iter().map(|row| row[x]).collect::<Vec<_>>();
+                match settle_line(&mut col, groups) {
+                    Some(c) => changed |= c,
+                    None => return false,
+                }
+                for (row, cell) in picture.iter_mut().zip(col) {
+                    row[x] = cell;
+                }
+            }
+        }
+        true
+    }
+
+    /// Up to `limit` solutions, so asking for two tells whether the puzzle is unique.
+    pub fn solve(&self, limit: usize) -> Vec<Picture> {
+        let mut solutions = vec![];
+        let mut stack = vec![vec![vec![State::Maybe; self.cols.len()]; self.rows.len()]];
+        while let Some(mut picture) = stack.pop() {
+            if solutions.len() >= limit {
+                break;
+            }
+            if !self.propagate(&mut picture) {
+                continue;
+            }
+            let unknown = picture
+                .iter()
+                .enumerate()
+                .find_map(|(y, row)| row.iter().position(|c| *c == State::Maybe).map(|x| (x, y)));
+            match unknown {
+                None => solutions.push(picture),
+                Some((x, y)) => {
+                    // Guess the cell both ways, filled first
+                    let mut empty = picture.clone();
+                    empty[y][x] = State::No;
+                    picture[y][x] = State::Yes;
+                    stack.push(empty);
+                    stack.push(picture);
+                }
+            }
+        }
+        solutions
+    }
+}
+
+/// The picture as `#` and `.`, with `?` for cells that are still unknown.
+pub fn render(picture: &Picture) -> String {
+    picture
+        .iter()
+        .map(|row| row.iter().map(|c| c.to_string()).collect::<String>() + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(s: &str) -> Vec<State> {
+        s.chars().map(State::from).collect()
+    }
+
+    #[test]
+    fn test_line_options() {
+        // An 8 in 10 cells always covers the middle 6
+        let options = line_options(&line("??????????"), &[8]).unwrap();
+        let forced = options
+            .iter()
+            .map(|o| match o {
+                (true, false) => '#',
+                (false, true) => '.',
+                _ => '?',
+            })
+            .collect::<String>();
+        assert_eq!(forced, "??######??");
+
+        let mut cells = line("?#???.????");
+        assert_eq!(settle_line(&mut cells, &[3, 4]), Some(true));
+        assert_eq!(cells, line("?##?..####"));
+        assert_eq!(settle_line(&mut line("#.#"), &[3]), None);
+    }
+
+    #[test]
+    fn test_matches_record_count() {
+        // A line has a choice exactly when the springs have more than one arrangement
+        for record in [
+            "???.### 1,1,3",
+            "?###???????? 3,2,1",
+            "?#?#?#?#?#?#?#? 1,3,1,6",
+        ] {
+            let record = crate::Record::parse(record, 1).unwrap();
+            let mut cells = record.springs.clone();
+            settle_line(&mut cells, &record.groups).unwrap();
+            assert_eq!(cells.contains(&State::Maybe), record.count() > 1);
+        }
+    }
+}
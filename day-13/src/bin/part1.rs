@@ -1,85 +1,26 @@
+use day_13::{parse_patterns, report, smudges_from_args, summarize, ReflectionError};
 use std::time::Instant;
 
-fn find_symetry_col(chunk: &str) -> Option<i64> {
-    let lines = chunk.lines().collect::<Vec<_>>();
-
-    let mut valid_cols = (0..lines[0].len()).collect::<Vec<usize>>();
-    for l in lines {
-        let len = l.len();
-        let mut cols: Vec<usize> = vec![];
-
-        for i in 1..len {
-            let first_halve: &str;
-            let second_halve: &str;
-            if i <= len / 2 {
-                first_halve = &l[..i];
-                second_halve = &l[i..i + i];
-            } else {
-                first_halve = &l[i - (len - i)..i];
-                second_halve = &l[i..];
-            }
-
-            if first_halve == second_halve.chars().rev().collect::<String>() {
-                cols.push(i);
-            }
-        }
-
-        valid_cols = valid_cols
-            .into_iter()
-            .filter(|&x| cols.contains(&x))
-            .collect::<Vec<usize>>();
-        if valid_cols.is_empty() {
-            return None;
-        }
-    }
-    Some(valid_cols[0] as i64)
-}
-
-fn find_symetry_row(chunk: &str) -> Option<i64> {
-    // transpose lines to columns and combine into a string.
-    let lines = chunk
-        .lines()
-        .map(|l| l.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut new_chunk = vec![];
-    for col in 0..lines[0].len() {
-        for row in 0..lines.len() {
-            new_chunk.push(lines[row][col]);
-        }
-        new_chunk.push('\n');
-    }
-
-    let newstr = new_chunk.iter().collect::<String>();
-    println!("Original chunk:");
-    println!("{}", chunk);
-    println!("Transposed chunk:");
-    println!("{}", newstr);
-    return find_symetry_col(&newstr);
-}
-
-fn solve(file: &str) -> i64 {
-    let chunks: Vec<_> = file.split("\n\n").collect();
-
-    let mut out = 0;
-    for chunk in chunks {
-        if let Some(c) = find_symetry_col(chunk) {
-            out += c;
-        } else if let Some(r) = find_symetry_row(chunk) {
-            out += 100 * r;
-        } else {
-            println!("{}", chunk);
-            panic!("No symetry found");
-        }
-    }
-    out
+fn solve(file: &str, smudges: u32) -> Result<usize, ReflectionError> {
+    summarize(&parse_patterns(file), smudges)
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let smudges = smudges_from_args(0);
+    if std::env::args().any(|a| a == "--report") {
+        print!("{}", report(&parse_patterns(input)));
+    }
+
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = match solve(input, smudges) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let duration = t0.elapsed();
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
@@ -88,6 +29,16 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day_13::{Axis, Pattern};
+
+    fn horizontal_lines(input: &str, smudges: u32) -> Vec<usize> {
+        Pattern::parse(input)
+            .with_smudges(smudges)
+            .iter()
+            .filter(|r| r.axis == Axis::Horizontal)
+            .map(|r| r.position)
+            .collect()
+    }
 
     #[test]
     fn test_whole_part() {
@@ -106,7 +57,7 @@ mod tests {
 #####.##.
 ..##..###
 #....#..#";
-        assert_eq!(solve(input), 405);
+        assert_eq!(solve(input, 0), Ok(405));
     }
 
     #[test]
@@ -122,7 +73,7 @@ mod tests {
 ######..########.
 .#..#....#.##.#..
 ##...###.#....#.#";
-        assert_eq!(find_symetry_row(input), Some(3));
+        assert_eq!(horizontal_lines(input, 0), vec![3]);
     }
 
     #[test]
@@ -142,6 +93,31 @@ mod tests {
 .####.#.#..##
 .####.#.##...
 .####.#.##...";
-        assert_eq!(find_symetry_row(input), Some(14));
+        assert_eq!(horizontal_lines(input, 0), vec![14]);
+    }
+
+    #[test]
+    fn test_errors_and_report() {
+        // Every column and row differs from its neighbour
+        let input = "#.\n##";
+        assert_eq!(
+            solve(input, 0),
+            Err(ReflectionError::NotFound {
+                pattern: 1,
+                smudges: 0
+            })
+        );
+        assert_eq!(
+            report(&parse_patterns(input)),
+            "Pattern 1:
+  vertical line at 1: 1 mismatches
+  horizontal line at 1: 1 mismatches
+"
+        );
+        // With one smudge either line works
+        assert!(matches!(
+            solve(input, 1),
+            Err(ReflectionError::Ambiguous { pattern: 1, .. })
+        ));
     }
 }
@@ -1,84 +1,26 @@
+use day_13::{parse_patterns, report, smudges_from_args, summarize, ReflectionError};
 use std::time::Instant;
 
-fn find_symetry_col(chunk: &str) -> Option<i64> {
-    let lines = chunk.lines().collect::<Vec<_>>();
-
-    let mut mistakes = vec![0; lines[0].len()];
-    for l in lines {
-        let len = l.len();
-
-        for i in 1..len {
-            let first_halve: &str;
-            let second_halve: &str;
-            if i <= len / 2 {
-                first_halve = &l[..i];
-                second_halve = &l[i..i + i];
-            } else {
-                first_halve = &l[i - (len - i)..i];
-                second_halve = &l[i..];
-            }
-            let rev_second = second_halve.chars().rev().collect::<String>();
-
-            for (n, c) in first_halve.chars().enumerate() {
-                if c != rev_second.chars().nth(n).unwrap() {
-                    mistakes[i] += 1;
-                }
-            }
-        }
-    }
-    println!("{:?}", mistakes);
-    mistakes
-        .iter()
-        .enumerate()
-        .find(|(_, m)| **m == 1)
-        .map(|(i, _)| i as i64)
-}
-
-fn find_symetry_row(chunk: &str) -> Option<i64> {
-    // transpose lines to columns and combine into a string.
-    let lines = chunk
-        .lines()
-        .map(|l| l.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
-
-    let mut new_chunk = vec![];
-    for col in 0..lines[0].len() {
-        for row in 0..lines.len() {
-            new_chunk.push(lines[row][col]);
-        }
-        new_chunk.push('\n');
-    }
-
-    let newstr = new_chunk.iter().collect::<String>();
-    println!("Original chunk:");
-    println!("{}", chunk);
-    println!("Transposed chunk:");
-    println!("{}", newstr);
-    return find_symetry_col(&newstr);
-}
-
-fn solve(file: &str) -> i64 {
-    let chunks: Vec<_> = file.split("\n\n").collect();
-
-    let mut out = 0;
-    for chunk in chunks {
-        if let Some(c) = find_symetry_col(chunk) {
-            out += c;
-        } else if let Some(r) = find_symetry_row(chunk) {
-            out += 100 * r;
-        } else {
-            println!("{}", chunk);
-            panic!("No symetry found");
-        }
-    }
-    out
+fn solve(file: &str, smudges: u32) -> Result<usize, ReflectionError> {
+    summarize(&parse_patterns(file), smudges)
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let smudges = smudges_from_args(1);
+    if std::env::args().any(|a| a == "--report") {
+        print!("{}", report(&parse_patterns(input)));
+    }
+
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = match solve(input, smudges) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let duration = t0.elapsed();
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
@@ -87,6 +29,15 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use day_13::{Axis, Pattern, Reflection};
+
+    /// The only mirror line with one smudge fixed.
+    fn smudged_line(input: &str) -> Reflection {
+        match Pattern::parse(input).with_smudges(1)[..] {
+            [line] => line,
+            ref lines => panic!("Expected one line, found {:?}", lines),
+        }
+    }
 
     #[test]
     fn test_whole_part() {
@@ -105,11 +56,11 @@ mod tests {
 #####.##.
 ..##..###
 #....#..#";
-        assert_eq!(solve(input), 400);
+        assert_eq!(solve(input, 1), Ok(400));
     }
 
-    // #[test]
-    fn test_symetry_row() {
+    #[test]
+    fn test_smudged_symetry() {
         let input = "#.##..#..######..
 ....##.##..##..##
 ###...##..#..#..#
@@ -121,11 +72,19 @@ mod tests {
 ######..########.
 .#..#....#.##.#..
 ##...###.#....#.#";
-        assert_eq!(find_symetry_row(input), Some(3));
+        // Clean, rows 3 and 4 mirror each other, but fixing a smudge moves the line
+        assert_eq!(
+            smudged_line(input),
+            Reflection {
+                axis: Axis::Vertical,
+                position: 12,
+                mismatches: 1
+            }
+        );
     }
 
-    // #[test]
-    fn test_symetry_row_2() {
+    #[test]
+    fn test_smudged_symetry_2() {
         let input = ".......##..##
 ..##...#.#...
 .#..#...#.###
@@ -141,6 +100,7 @@ mod tests {
 .####.#.#..##
 .####.#.##...
 .####.#.##...";
-        assert_eq!(find_symetry_row(input), Some(14));
+        assert_eq!(smudged_line(input).axis, Axis::Vertical);
+        assert_eq!(smudged_line(input).position, 3);
     }
 }
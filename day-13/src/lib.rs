@@ -0,0 +1,176 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// A line between two columns, counted by the columns left of it.
+    Vertical,
+    /// A line between two rows, counted by the rows above it.
+    Horizontal,
+}
+
+/// A mirror line and how many cells differ from their reflection across it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reflection {
+    pub axis: Axis,
+    pub position: usize,
+    pub mismatches: u32,
+}
+
+impl Reflection {
+    /// The puzzle's summary: columns left of a vertical line, or 100 times the rows above.
+    pub fn summary(&self) -> usize {
+        match self.axis {
+            Axis::Vertical => self.position,
+            Axis::Horizontal => 100 * self.position,
+        }
+    }
+}
+
+impl fmt::Display for Reflection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let axis = match self.axis {
+            Axis::Vertical => "vertical",
+            Axis::Horizontal => "horizontal",
+        };
+        write!(
+            f,
+            "{} line at {}: {} mismatches",
+            axis, self.position, self.mismatches
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReflectionError {
+    NotFound {
+        pattern: usize,
+        smudges: u32,
+    },
+    Ambiguous {
+        pattern: usize,
+        lines: Vec<Reflection>,
+    },
+}
+
+impl fmt::Display for ReflectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReflectionError::NotFound { pattern, smudges } => write!(
+                f,
+                "Pattern {} has no reflection with exactly {} smudges",
+                pattern, smudges
+            ),
+            ReflectionError::Ambiguous { pattern, lines } => {
+                write!(f, "Pattern {} has {} reflections:", pattern, lines.len())?;
+                for line in lines {
+                    write!(f, "\n  {}", line)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A pattern of ash and rocks, with every row and column as a bitmask of its rocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub rows: Vec<u64>,
+    pub cols: Vec<u64>,
+}
+
+/// Cells that differ when `lines` is folded between `lines[i - 1]` and `lines[i]`.
+fn fold_mismatches(lines: &[u64], i: usize) -> u32 {
+    lines[..i]
+        .iter()
+        .rev()
+        .zip(lines[i..].iter())
+        .map(|(a, b)| (a ^ b).count_ones())
+        .sum()
+}
+
+impl Pattern {
+    pub fn parse(chunk: &str) -> Self {
+        let lines = chunk
+            .lines()
+            .map(|l| l.chars().map(|c| c == '#').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert!(lines[0].len() <= 64 && lines.len() <= 64, "Pattern too big");
+
+        let rows = lines
+            .iter()
+            .map(|l| l.iter().fold(0, |mask, &rock| (mask << 1) | rock as u64))
+            .collect();
+        let cols = (0..lines[0].len())
+            .map(|x| lines.iter().fold(0, |mask, l| (mask << 1) | l[x] as u64))
+            .collect();
+        Pattern { rows, cols }
+    }
+
+    /// Every possible mirror line, vertical ones first, with its mismatch count.
+    pub fn reflections(&self) -> Vec<Reflection> {
+        let vertical = (1..self.cols.len()).map(|i| Reflection {
+            axis: Axis::Vertical,
+            position: i,
+            mismatches: fold_mismatches(&self.cols, i),
+        });
+        let horizontal = (1..self.rows.len()).map(|i| Reflection {
+            axis: Axis::Horizontal,
+            position: i,
+            mismatches: fold_mismatches(&self.rows, i),
+        });
+        vertical.chain(horizontal).collect()
+    }
+
+    /// The mirror lines that need exactly `smudges` cells fixed.
+    pub fn with_smudges(&self, smudges: u32) -> Vec<Reflection> {
+        self.reflections()
+            .into_iter()
+            .filter(|r| r.mismatches == smudges)
+            .collect()
+    }
+}
+
+pub fn parse_patterns(file: &str) -> Vec<Pattern> {
+    file.split("\n\n").map(Pattern::parse).collect()
+}
+
+/// Sum of the summaries, each pattern needing exactly one line with `smudges` mismatches.
+pub fn summarize(patterns: &[Pattern], smudges: u32) -> Result<usize, ReflectionError> {
+    patterns
+        .iter()
+        .enumerate()
+        .map(|(i, p)| match &p.with_smudges(smudges)[..] {
+            [line] => Ok(line.summary()),
+            [] => Err(ReflectionError::NotFound {
+                pattern: i + 1,
+                smudges,
+            }),
+            lines => Err(ReflectionError::Ambiguous {
+                pattern: i + 1,
+                lines: lines.to_vec(),
+            }),
+        })
+        .sum()
+}
+
+/// Every mirror line of every pattern, for `--report`.
+pub fn report(patterns: &[Pattern]) -> String {
+    let mut out = String::new();
+    for (i, p) in patterns.iter().enumerate() {
+        out += &format!("Pattern {}:\n", i + 1);
+        for line in p.reflections() {
+            out += &format!("  {}\n", line);
+        }
+    }
+    out
+}
+
+/// The smudge count from `--smudges`, or `default` without one.
+pub fn smudges_from_args(default: u32) -> u32 {
+    let args = std::env::args().collect::<Vec<_>>();
+    args.iter()
+        .position(|a| a == "--smudges")
+        .and_then(|i| args.get(i + 1))
+        .map(|n| n.parse().unwrap())
+        .unwrap_or(default)
+}
@@ -1,29 +1,10 @@
+use day_14::{Direction, Platform};
 use std::time::Instant;
 
-fn solve(file: &str) -> i64 {
-    let matrix: Vec<Vec<char>> = file
-        .lines()
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect();
-
-    let mut result = 0;
-    for column in 0..matrix[0].len() {
-        let mut current_index = matrix.len();
-        for row in 0..matrix.len() {
-            match matrix[row][column] {
-                '#' => {
-                    current_index = matrix.len() - row - 1;
-                }
-                'O' => {
-                    result += current_index;
-                    current_index -= 1;
-                }
-                _ => {}
-            }
-        }
-    }
-
-    result as i64
+fn solve(file: &str) -> usize {
+    let mut platform = Platform::parse(file);
+    platform.tilt(Direction::North);
+    platform.load(Direction::North)
 }
 
 fn main() {
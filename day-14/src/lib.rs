@@ -0,0 +1,316 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    West,
+    South,
+    East,
+}
+
+impl Direction {
+    /// The order of one spin cycle.
+    pub fn cycle() -> [Direction; 4] {
+        [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ]
+    }
+}
+
+/// The platform as bitboards, both one `u128` per row with bit `x` for column `x` and one per
+/// column with bit `y` for row `y`. West and east tilts slide the rows, north and south tilts
+/// the columns, and the other view is rebuilt after each tilt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    width: usize,
+    height: usize,
+    rolling: Vec<u128>,
+    solid: Vec<u128>,
+    rolling_columns: Vec<u128>,
+    solid_columns: Vec<u128>,
+}
+
+/// Bit `y` of column `x` for every bit `x` of row `y`.
+fn transpose(lines: &[u128], length: usize) -> Vec<u128> {
+    let mut transposed = vec![0; length];
+    for (y, line) in lines.iter().enumerate() {
+        let mut bits = *line;
+        while bits != 0 {
+            let x = bits.trailing_zeros() as usize;
+            transposed[x] |= 1 << y;
+            bits &= bits - 1;
+        }
+    }
+    transposed
+}
+
+/// All `n` low bits set.
+fn full_line(n: usize) -> u128 {
+    u128::MAX.checked_shr(128 - n as u32).unwrap_or(0)
+}
+
+/// Rolls the rocks of every line towards bit 0, or towards the high bits without `to_low`.
+fn slide(rolling: &mut [u128], solid: &[u128], length: usize, to_low: bool) {
+    let full = full_line(length);
+    for (line, solid) in rolling.iter_mut().zip(solid) {
+        // Every step moves each rock that has a free cell next to it
+        loop {
+            let free = !(*line | solid) & full;
+            let moving = match to_low {
+                true => *line & (free << 1),
+                false => *line & (free >> 1),
+            };
+            if moving == 0 {
+                break;
+            }
+            *line ^= moving;
+            *line |= match to_low {
+                true => moving >> 1,
+                false => moving << 1,
+            };
+        }
+    }
+}
+
+impl Platform {
+    pub fn parse(file: &str) -> Self {
+        let lines = file.lines().collect::<Vec<_>>();
+        let width = lines.first().map_or(0, |l| l.len());
+        let height = lines.len();
+        assert!(width <= 128, "Platform wider than 128 columns");
+        assert!(height <= 128, "Platform taller than 128 rows");
+
+        let mask_of = |line: &str, rock: char| {
+            line.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == rock)
+                .fold(0_u128, |mask, (x, _)| mask | 1 << x)
+        };
+        let rolling = lines.iter().map(|l| mask_of(l, 'O')).collect::<Vec<_>>();
+        let solid = lines.iter().map(|l| mask_of(l, '#')).collect::<Vec<_>>();
+        Platform {
+            width,
+            height,
+            rolling_columns: transpose(&rolling, width),
+            solid_columns: transpose(&solid, width),
+            rolling,
+            solid,
+        }
+    }
+
+    /// Rolls every round rock as far as it goes.
+    pub fn tilt(&mut self, direction: Direction) {
+        match direction {
+            Direction::North | Direction::South => {
+                let north = direction == Direction::North;
+                slide(
+                    &mut self.rolling_columns,
+                    &self.solid_columns,
+                    self.height,
+                    north,
+                );
+                self.rolling = transpose(&self.rolling_columns, self.height);
+            }
+            Direction::West | Direction::East => {
+                let west = direction == Direction::West;
+                slide(&mut self.rolling, &self.solid, self.width, west);
+                self.rolling_columns = transpose(&self.rolling, self.width);
+            }
+        }
+    }
+
+    /// Tilts north, west, south and east once.
+    pub fn spin_cycle(&mut self) {
+        for direction in Direction::cycle() {
+            self.tilt(direction);
+        }
+    }
+
+    fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.rolling.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Runs `cycles` spin cycles, skipping ahead once the rocks repeat a layout. Only the hash
+    /// of each layout is kept, and the cycles left over after skipping are run for real.
+    pub fn spin(&mut self, cycles: usize) {
+        let mut seen: HashMap<u64, usize> = HashMap::new();
+        let mut i = 0;
+        while i < cycles {
+            if let Some(start) = seen.insert(self.state_hash(), i) {
+                let cycle_length = i - start;
+                for _ in 0..(cycles - i) % cycle_length {
+                    self.spin_cycle();
+                }
+                return;
+            }
+            self.spin_cycle();
+            i += 1;
+        }
+    }
+
+    /// Total load of the round rocks on the beams of the given edge.
+    pub fn load(&self, edge: Direction) -> usize {
+        let mut load = 0;
+        for (y, row) in self.rolling.iter().enumerate() {
+            let count = row.count_ones() as usize;
+            load += match edge {
+                Direction::North => count * (self.height - y),
+                Direction::South => count * (y + 1),
+                Direction::West => (0..self.width)
+                    .filter(|x| row >> x & 1 == 1)
+                    .map(|x| self.width - x)
+                    .sum(),
+                Direction::East => (0..self.width)
+                    .filter(|x| row >> x & 1 == 1)
+                    .map(|x| x + 1)
+                    .sum(),
+            };
+        }
+        load
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let c = match (self.rolling[y] >> x & 1, self.solid[y] >> x & 1) {
+                    (1, _) => 'O',
+                    (_, 1) => '#',
+                    _ => '.',
+                };
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn test_tilt_north() {
+        let mut platform = Platform::parse(INPUT);
+        platform.tilt(Direction::North);
+        assert_eq!(
+            platform.to_string(),
+            "OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#....
+"
+        );
+        assert_eq!(platform.load(Direction::North), 136);
+    }
+
+    #[test]
+    fn test_spin_cycles() {
+        let mut platform = Platform::parse(INPUT);
+        platform.spin_cycle();
+        assert_eq!(
+            platform.to_string(),
+            ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#....
+"
+        );
+        platform.spin_cycle();
+        platform.spin_cycle();
+        assert_eq!(
+            platform.to_string(),
+            ".....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#...O###.O
+#.OOO#...O
+"
+        );
+
+        let mut skipped = Platform::parse(INPUT);
+        skipped.spin(3);
+        assert_eq!(skipped, platform);
+    }
+
+    #[test]
+    fn test_spin_skips_ahead() {
+        // Skipping through the repeating part ends where spinning one cycle at a time does
+        for cycles in [10, 25, 101] {
+            let mut slow = Platform::parse(INPUT);
+            for _ in 0..cycles {
+                slow.spin_cycle();
+            }
+            let mut fast = Platform::parse(INPUT);
+            fast.spin(cycles);
+            assert_eq!(fast, slow, "after {} cycles", cycles);
+        }
+    }
+
+    #[test]
+    fn test_empty_platform() {
+        let mut platform = Platform::parse("");
+        for direction in Direction::cycle() {
+            platform.tilt(direction);
+            assert_eq!(platform.load(direction), 0);
+        }
+        platform.spin(1_000_000_000);
+        assert_eq!(platform.to_string(), "");
+    }
+
+    #[test]
+    fn test_load_on_every_edge() {
+        let platform = Platform::parse("O.#\n..O\n.O.");
+        assert_eq!(platform.load(Direction::North), 3 + 2 + 1);
+        assert_eq!(platform.load(Direction::South), 1 + 2 + 3);
+        assert_eq!(platform.load(Direction::West), 3 + 1 + 2);
+        assert_eq!(platform.load(Direction::East), 1 + 3 + 2);
+
+        let mut platform = platform;
+        platform.tilt(Direction::East);
+        assert_eq!(platform.to_string(), ".O#\n..O\n..O\n");
+        platform.tilt(Direction::South);
+        assert_eq!(platform.to_string(), "..#\n..O\n.OO\n");
+        platform.tilt(Direction::West);
+        assert_eq!(platform.to_string(), "..#\nO..\nOO.\n");
+    }
+}
@@ -1,17 +1,8 @@
+use day_15::hash;
 use std::time::Instant;
 
-fn solve(file: &str) -> i64 {
-    file.split(',')
-        .map(|w| {
-            w.trim()
-                .chars()
-                // .inspect(|c| println! {"Char: {}\t Ascii: {}", c, *c as usize})
-                .fold(0, |acc, c| {
-                    (((acc as usize + c as usize) * 17) % 256) as i64
-                })
-        })
-        .inspect(|v| println! {"Value: {}", v})
-        .sum()
+fn solve(file: &str) -> usize {
+    file.trim().split(',').map(hash).sum()
 }
 
 fn main() {
@@ -1,97 +1,33 @@
+use day_15::{parse_steps, trace, LensBoxes};
 use std::time::Instant;
 
-enum Operator {
-    Equal,
-    Dash,
-}
-
-impl From<char> for Operator {
-    fn from(c: char) -> Self {
-        match c {
-            '=' => Operator::Equal,
-            '-' => Operator::Dash,
-            _ => panic!("Invalid operator {}", c),
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Lens {
-    focal_length: usize,
-    label: String,
-}
-
-fn get_hash(key: &str) -> usize {
-    let mut hash: usize = 0;
-    for c in key.chars() {
-        hash = ((hash + c as usize) * 17) % 256;
+/// The focusing power after the first `stop` steps, or all of them.
+fn solve(file: &str, stop: Option<usize>) -> usize {
+    let mut boxes = LensBoxes::new();
+    for step in parse_steps(file).iter().take(stop.unwrap_or(usize::MAX)) {
+        boxes.apply(step);
     }
-    hash
+    boxes.focusing_power()
 }
 
-fn solve(file: &str) -> i64 {
-    let mut boxes: Vec<Vec<Lens>> = vec![vec![]; 256];
-    file.split(',').for_each(|w| {
-        // for each instruction
-        let (label, operator, value_str) = w.trim().chars().fold(
-            (String::new(), Operator::Equal, String::new()),
-            |(mut label, mut operator, mut value_str), c| {
-                match c {
-                    'a'..='z' => label.push(c),
-                    '0'..='9' => value_str.push(c),
-                    _ => operator = Operator::from(c),
-                }
-                (label, operator, value_str)
-            },
-        );
-
-        let hash = get_hash(&label);
-        match operator {
-            Operator::Equal => {
-                // set hash to value
-                let value = value_str.parse::<usize>().unwrap();
-
-                if let Some(lens) = boxes[hash].iter_mut().find(|l| l.label == label) {
-                    lens.focal_length = value;
-                } else {
-                    boxes[hash].push(Lens {
-                        focal_length: value,
-                        label: label.clone(),
-                    });
-                }
-            }
-            Operator::Dash => {
-                // remove lens
-                if let Some(index) = boxes[hash].iter().position(|l| l.label == *label) {
-                    // .iter().position(|l| l.label == label) {
-                    {
-                        boxes[hash].remove(index);
-                    }
-                }
-            }
-        }
-    });
-
-    // Compute focussing power of hashmap
-    boxes
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let stop = args
         .iter()
-        .enumerate()
-        .map(|(box_ind, box_arr)| {
-            (box_ind + 1)
-                * box_arr
-                    .iter()
-                    .enumerate()
-                    .map(|(lens_ind, lens)| (lens_ind + 1) * lens.focal_length)
-                    .sum::<usize>() as usize
-        })
-        .sum::<usize>() as i64
-}
+        .position(|a| a == "--stop")
+        .and_then(|i| args.get(i + 1))
+        .map(|n| n.parse::<usize>().unwrap());
 
-fn main() {
     let input = include_str!("input.txt");
+    if args.iter().any(|a| a == "--trace") {
+        let steps = parse_steps(input);
+        let steps = &steps[..stop.unwrap_or(steps.len()).min(steps.len())];
+        print!("{}", trace(&mut LensBoxes::new(), steps));
+    }
+
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = solve(input, stop);
     let duration = t0.elapsed();
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
@@ -104,6 +40,39 @@ mod tests {
     #[test]
     fn test_whole_part() {
         let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        assert_eq!(solve(input), 145);
+        assert_eq!(solve(input, None), 145);
+        // Box 0: [rn 1] [cm 2], Box 3: [pc 4] [ot 9] [ab 5]
+        assert_eq!(solve(input, Some(8)), 1 + 4 + 4 * (4 + 18 + 15));
+    }
+
+    #[test]
+    fn test_trace() {
+        let steps = parse_steps("rn=1,cm-,qp=3,cm=2,qp-");
+        let mut boxes = LensBoxes::new();
+        assert_eq!(
+            trace(&mut boxes, &steps[..3]),
+            "Insert rn (box 0) with focal length 1
+After \"rn=1\":
+Box 0: [rn 1]
+
+Remove cm (box 0)
+After \"cm-\":
+Box 0: [rn 1]
+
+Insert qp (box 1) with focal length 3
+After \"qp=3\":
+Box 0: [rn 1]
+Box 1: [qp 3]
+
+"
+        );
+        // Carries on from where it stopped
+        assert!(trace(&mut boxes, &steps[3..]).ends_with(
+            "Remove qp (box 1)
+After \"qp-\":
+Box 0: [rn 1] [cm 2]
+
+"
+        ));
     }
 }
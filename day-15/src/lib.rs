@@ -0,0 +1,296 @@
+use std::{collections::HashMap, fmt};
+
+/// The Holiday ASCII String Helper algorithm.
+pub fn hash(s: &str) -> usize {
+    s.bytes().fold(0, |acc, c| ((acc + c as usize) * 17) % 256)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// `label=3`: put a lens with this focal length in the label's box.
+    Insert(usize),
+    /// `label-`: take the label's lens out of its box.
+    Remove,
+}
+
+/// One comma separated step of the initialization sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub label: String,
+    pub operation: Operation,
+}
+
+impl From<&str> for Step {
+    fn from(s: &str) -> Self {
+        // Split on the operator at the end, so labels may hold any other characters
+        let s = s.trim();
+        if let Some(label) = s.strip_suffix('-') {
+            return Step {
+                label: label.to_string(),
+                operation: Operation::Remove,
+            };
+        }
+        let (label, focal_length) = s
+            .rsplit_once('=')
+            .unwrap_or_else(|| panic!("Invalid step {}", s));
+        Step {
+            label: label.to_string(),
+            operation: Operation::Insert(focal_length.parse().unwrap()),
+        }
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Operation::Insert(focal_length) => write!(f, "{}={}", self.label, focal_length),
+            Operation::Remove => write!(f, "{}-", self.label),
+        }
+    }
+}
+
+impl Step {
+    /// What the step does, e.g. `Insert rn (box 0) with focal length 1`.
+    pub fn describe(&self) -> String {
+        match self.operation {
+            Operation::Insert(focal_length) => format!(
+                "Insert {} (box {}) with focal length {}",
+                self.label,
+                hash(&self.label),
+                focal_length
+            ),
+            Operation::Remove => format!("Remove {} (box {})", self.label, hash(&self.label)),
+        }
+    }
+}
+
+pub fn parse_steps(file: &str) -> Vec<Step> {
+    file.trim().split(',').map(Step::from).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lens {
+    pub label: String,
+    pub focal_length: usize,
+}
+
+/// The lenses of one box in slot order. Removed lenses leave a gap until half the slots are
+/// gaps, so removing does not shift every lens behind it each time.
+#[derive(Debug, Clone, Default)]
+struct LensBox {
+    slots: Vec<Option<Lens>>,
+    /// Label to its index in `slots`.
+    index: HashMap<String, usize>,
+}
+
+impl LensBox {
+    fn lenses(&self) -> impl Iterator<Item = &Lens> {
+        self.slots.iter().flatten()
+    }
+
+    fn insert(&mut self, label: &str, focal_length: usize) {
+        match self.index.get(label) {
+            Some(&i) => self.slots[i].as_mut().unwrap().focal_length = focal_length,
+            None => {
+                self.index.insert(label.to_string(), self.slots.len());
+                self.slots.push(Some(Lens {
+                    label: label.to_string(),
+                    focal_length,
+                }));
+            }
+        }
+    }
+
+    fn remove(&mut self, label: &str) -> Option<Lens> {
+        let i = self.index.remove(label)?;
+        let lens = self.slots[i].take();
+        if self.slots.len() >= 2 * self.index.len() {
+            self.compact();
+        }
+        lens
+    }
+
+    fn compact(&mut self) {
+        self.slots.retain(Option::is_some);
+        for (i, lens) in self.slots.iter().flatten().enumerate() {
+            *self.index.get_mut(&lens.label).unwrap() = i;
+        }
+    }
+}
+
+/// The 256 boxes of the lens library, with each lens in the box its label hashes to.
+#[derive(Debug, Clone)]
+pub struct LensBoxes {
+    boxes: Vec<LensBox>,
+}
+
+impl Default for LensBoxes {
+    fn default() -> Self {
+        LensBoxes::new()
+    }
+}
+
+impl LensBoxes {
+    pub fn new() -> Self {
+        LensBoxes {
+            boxes: vec![LensBox::default(); 256],
+        }
+    }
+
+    /// Replaces the focal length of a lens already there, or adds the lens behind the others.
+    pub fn insert(&mut self, label: &str, focal_length: usize) {
+        self.boxes[hash(label)].insert(label, focal_length);
+    }
+
+    pub fn remove(&mut self, label: &str) -> Option<Lens> {
+        self.boxes[hash(label)].remove(label)
+    }
+
+    pub fn get(&self, label: &str) -> Option<&Lens> {
+        let lens_box = &self.boxes[hash(label)];
+        lens_box
+            .index
+            .get(label)
+            .and_then(|&i| lens_box.slots[i].as_ref())
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        match step.operation {
+            Operation::Insert(focal_length) => self.insert(&step.label, focal_length),
+            Operation::Remove => {
+                self.remove(&step.label);
+            }
+        }
+    }
+
+    /// Every lens as `(box, slot, lens)`, counting both from 0, in box then slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Lens)> {
+        self.boxes
+            .iter()
+            .enumerate()
+            .flat_map(|(box_ind, lens_box)| {
+                lens_box
+                    .lenses()
+                    .enumerate()
+                    .map(move |(slot, lens)| (box_ind, slot, lens))
+            })
+    }
+
+    pub fn focusing_power(&self) -> usize {
+        self.iter()
+            .map(|(box_ind, slot, lens)| (box_ind + 1) * (slot + 1) * lens.focal_length)
+            .sum()
+    }
+}
+
+/// The non-empty boxes as in the puzzle, e.g. `Box 0: [rn 1] [cm 2]`.
+impl fmt::Display for LensBoxes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (box_ind, lens_box) in self.boxes.iter().enumerate() {
+            if lens_box.index.is_empty() {
+                continue;
+            }
+            write!(f, "Box {}:", box_ind)?;
+            for lens in lens_box.lenses() {
+                write!(f, " [{} {}]", lens.label, lens.focal_length)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the steps one at a time, writing out the boxes after each one exactly like the
+/// puzzle's walkthrough, with a line describing the operation above every block.
+pub fn trace(boxes: &mut LensBoxes, steps: &[Step]) -> String {
+    let mut out = String::new();
+    for step in steps {
+        boxes.apply(step);
+        out += &format!("{}\nAfter \"{}\":\n{}\n", step.describe(), step, boxes);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash("HASH"), 52);
+        assert_eq!(hash("rn"), 0);
+        assert_eq!(hash("qp"), 1);
+    }
+
+    #[test]
+    fn test_steps() {
+        let steps = parse_steps("rn=1,cm-,a=b=7,x-y-\n");
+        assert_eq!(steps[0].operation, Operation::Insert(1));
+        assert_eq!(steps[1].label, "cm");
+        assert_eq!(steps[2].label, "a=b");
+        assert_eq!(steps[3].label, "x-y");
+        assert_eq!(
+            steps.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            vec!["rn=1", "cm-", "a=b=7", "x-y-"]
+        );
+    }
+
+    #[test]
+    fn test_lens_boxes() {
+        let mut boxes = LensBoxes::new();
+        for step in parse_steps("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7") {
+            boxes.apply(&step);
+        }
+        assert_eq!(
+            boxes.to_string(),
+            "Box 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]\n"
+        );
+        assert_eq!(boxes.get("ot").unwrap().focal_length, 7);
+        assert_eq!(boxes.get("qp"), None);
+        assert_eq!(
+            boxes
+                .iter()
+                .map(|(b, s, l)| (b, s, l.label.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 0, "rn"),
+                (0, 1, "cm"),
+                (3, 0, "ot"),
+                (3, 1, "ab"),
+                (3, 2, "pc")
+            ]
+        );
+        assert_eq!(boxes.focusing_power(), 145);
+    }
+
+    #[test]
+    fn test_many_removals() {
+        // Enough lenses in one box that removing half of them compacts it
+        let mut boxes = LensBoxes::new();
+        let labels = (0..)
+            .map(|n| format!("lens{}", n))
+            .filter(|l| hash(l) == 7)
+            .take(100)
+            .collect::<Vec<_>>();
+        for (i, label) in labels.iter().enumerate() {
+            boxes.insert(label, i % 9 + 1);
+        }
+        for label in labels.iter().step_by(2) {
+            assert!(boxes.remove(label).is_some());
+            assert!(boxes.remove(label).is_none());
+        }
+        assert!(boxes.boxes[7].slots.len() < 100);
+
+        let kept = labels.iter().skip(1).step_by(2).collect::<Vec<_>>();
+        assert_eq!(
+            boxes.iter().map(|(_, _, l)| &l.label).collect::<Vec<_>>(),
+            kept
+        );
+        for (box_ind, slot, lens) in boxes.iter() {
+            assert_eq!((box_ind, &lens.label), (7, kept[slot]));
+            assert_eq!(boxes.get(&lens.label), Some(lens));
+        }
+        boxes.insert(kept[0], 9);
+        assert_eq!(boxes.iter().next().unwrap().2.focal_length, 9);
+    }
+}
@@ -1,213 +1,42 @@
+use day_16::{threads_from_args, Beam, BeamGraph, Contraption};
 use std::time::Instant;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum GridItem {
-    Empty,
-    SplitVertical,
-    SplitHoriontal,
-    MirrorLeftUp,   // Same as MirrorRightDown
-    MirrorLeftDown, // Same as MirrorRightUp
-}
-impl From<char> for GridItem {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => GridItem::Empty,
-            '|' => GridItem::SplitVertical,
-            '-' => GridItem::SplitHoriontal,
-            '/' => GridItem::MirrorLeftUp,
-            '\\' => GridItem::MirrorLeftDown,
-            _ => panic!("Invalid grid item: {}", c),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn to_vec(&self) -> (i64, i64) {
-        match self {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        }
-    }
-
-    fn opposite(&self) -> Self {
-        match self {
-            Direction::Up => Direction::Down,
-            Direction::Down => Direction::Up,
-            Direction::Left => Direction::Right,
-            Direction::Right => Direction::Left,
-        }
-    }
-}
-
-struct Light {
-    position: (i64, i64),
-    direction: Direction,
-}
-
-fn count_light_positions(grid: &Vec<Vec<GridItem>>, light_endpoints: &mut Vec<Light>) -> i64 {
-    let width = grid[0].len();
-    let height = grid.len();
-    let mut light_directions: Vec<Vec<Vec<Direction>>> = vec![vec![vec![]; width]; height];
-    while let Some(mut light) = light_endpoints.pop() {
-        // Trace the endpoint, until it can go no further
-        loop {
-            let (x, y) = light.position;
-            //Check out of bounds
-            if x >= width as i64 || y >= height as i64 || x < 0 || y < 0 {
-                break;
-            }
-            let (x, y) = (x as usize, y as usize);
-
-            // check if it had already been there
-            if light_directions[y][x].contains(&light.direction) {
-                break;
-            }
-            // The direction does not matter for splitter
-            if grid[y][x] == GridItem::SplitHoriontal || grid[y][x] == GridItem::SplitVertical {
-                if !light_directions[y][x].is_empty() {
-                    break;
-                }
-            }
-            // Mirror are not symmetrical, light from left behaves differently than light from right
-            if grid[y][x] != GridItem::MirrorLeftUp && grid[y][x] != GridItem::MirrorLeftDown {
-                if light_directions[y][x].contains(&light.direction.opposite()) {
-                    break;
-                }
-            }
-
-            light_directions[y][x].push(light.direction);
-
-            // Move the light to the next grid position
-            let current_grid_item = &grid[y][x];
-            match current_grid_item {
-                GridItem::Empty => {
-                    let (dx, dy) = light.direction.to_vec();
-                    light.position = (x as i64 + dx, y as i64 + dy);
-                }
-                GridItem::SplitHoriontal => {
-                    // Create new endpoint to the left
-                    light_endpoints.push(Light {
-                        position: (x as i64 - 1, y as i64),
-                        direction: Direction::Left,
-                    });
-                    // Move the current light to the right
-                    light.position = (x as i64 + 1, y as i64);
-                    light.direction = Direction::Right;
-                }
-                GridItem::SplitVertical => {
-                    // Create new endpoint to the top
-                    light_endpoints.push(Light {
-                        position: (x as i64, y as i64 - 1),
-                        direction: Direction::Up,
-                    });
-                    // Move the current light to the bottom
-                    light.position = (x as i64, y as i64 + 1);
-                    light.direction = Direction::Down;
-                }
-                GridItem::MirrorLeftDown => {
-                    light.direction = match light.direction {
-                        Direction::Up => Direction::Left,
-                        Direction::Down => Direction::Right,
-                        Direction::Left => Direction::Up,
-                        Direction::Right => Direction::Down,
-                    };
-                    let (dx, dy) = light.direction.to_vec();
-                    light.position = (x as i64 + dx, y as i64 + dy);
-                }
-                GridItem::MirrorLeftUp => {
-                    light.direction = match light.direction {
-                        Direction::Up => Direction::Right,
-                        Direction::Down => Direction::Left,
-                        Direction::Left => Direction::Down,
-                        Direction::Right => Direction::Up,
-                    };
-                    let (dx, dy) = light.direction.to_vec();
-                    light.position = (x as i64 + dx, y as i64 + dy);
-                }
-            }
+/// The best entry from the condensed beam graph, and with `check` also from tracing every entry
+/// on its own, failing if the two disagree.
+fn solve(file: &str, threads: usize, check: bool) -> Result<(Beam, usize), String> {
+    let contraption = Contraption::parse(file);
+    let best = BeamGraph::new(&contraption).best_entry(threads);
+    if check {
+        let brute = contraption.best_entry_brute(threads);
+        if brute.1 != best.1 {
+            return Err(format!(
+                "Beam graph found {} tiles from {:?}, brute force {} from {:?}",
+                best.1, best.0, brute.1, brute.0
+            ));
         }
     }
-    // Count grid positions that have been visited by light
-    light_directions
-        .iter()
-        .map(|row| row.iter().filter(|v| !v.is_empty()).count() as i64)
-        .sum()
-}
-
-fn solve(file: &str) -> i64 {
-    let grid: Vec<Vec<GridItem>> = file
-        .lines()
-        .map(|line| line.chars().map(|c| c.into()).collect())
-        .collect();
-    let width = grid[0].len();
-    let height = grid.len();
-
-    let mut max_count = 0;
-
-    // horizontal edges
-    for x in 0..width {
-        // top-edge pointing down
-        let mut light_endpoints = vec![Light {
-            position: (x as i64, 0),
-            direction: Direction::Down,
-        }];
-        max_count = std::cmp::max(
-            max_count,
-            count_light_positions(&grid, &mut light_endpoints),
-        );
-
-        // bottom-edge pointing up
-        let mut light_endpoints = vec![Light {
-            position: (x as i64, height as i64 - 1),
-            direction: Direction::Up,
-        }];
-        max_count = std::cmp::max(
-            max_count,
-            count_light_positions(&grid, &mut light_endpoints),
-        );
-    }
-    // vertical edges
-    for y in 0..height {
-        // left-edge pointing right
-        let mut light_endpoints = vec![Light {
-            position: (0, y as i64),
-            direction: Direction::Right,
-        }];
-        max_count = std::cmp::max(
-            max_count,
-            count_light_positions(&grid, &mut light_endpoints),
-        );
-
-        // right-edge pointing left
-        let mut light_endpoints = vec![Light {
-            position: (width as i64 - 1, y as i64),
-            direction: Direction::Left,
-        }];
-        max_count = std::cmp::max(
-            max_count,
-            count_light_positions(&grid, &mut light_endpoints),
-        );
-    }
-
-    max_count
+    Ok(best)
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let threads = threads_from_args();
+    let check = std::env::args().any(|a| a == "--check");
+    let render = std::env::args().any(|a| a == "--render");
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let (entry, result) = match solve(input, threads, check) {
+        Ok(best) => best,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let duration = t0.elapsed();
+    if render {
+        print!("{}", Contraption::parse(input).render(entry, true));
+    }
+    println!("Best entry: {:?} at {:?}", entry.direction, entry.position);
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
 }
@@ -228,6 +57,21 @@ mod tests {
 .-.-/..|..
 .|....-|.\\
 ..//.|....";
-        assert_eq!(solve(input), 51);
+        let (entry, count) = solve(input, 4, true).unwrap();
+        assert_eq!(count, 51);
+        assert_eq!(entry.position, (3, 0));
+    }
+
+    #[test]
+    fn test_graph_matches_brute_force() {
+        // One where splitters send beams round in loops
+        let input = "-.\\.|\n.|.-.\n\\.-./\n./.|\\\n|-\\..";
+        let contraption = Contraption::parse(input);
+        for threads in [1, 3] {
+            assert_eq!(
+                solve(input, threads, true),
+                Ok(contraption.best_entry_brute(threads))
+            );
+        }
     }
 }
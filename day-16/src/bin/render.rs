@@ -0,0 +1,143 @@
+use day_16::{elements::ElementTable, threads_from_args, Beam, BeamGraph, Contraption, Direction};
+use std::time::Instant;
+
+/// An entry beam written as `x,y,dir`, with the direction as one of `^`, `v`, `<` and `>`.
+fn parse_entry(s: &str, contraption: &Contraption) -> Result<Beam, String> {
+    let invalid = || format!("Invalid entry {}, expected x,y,dir like 0,0,>", s);
+    let parts = s.split(',').collect::<Vec<_>>();
+    let [x, y, dir] = parts[..] else {
+        return Err(invalid());
+    };
+    let x = x.trim().parse::<usize>().map_err(|_| invalid())?;
+    let y = y.trim().parse::<usize>().map_err(|_| invalid())?;
+    let mut dir = dir.trim().chars();
+    let direction = match (dir.next(), dir.next()) {
+        (Some(c), None) => Direction::from_glyph(c).ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+    if x >= contraption.width() || y >= contraption.height() {
+        return Err(format!(
+            "Entry {} outside of the {}x{} grid",
+            s,
+            contraption.width(),
+            contraption.height()
+        ));
+    }
+    Ok(Beam {
+        position: (x, y),
+        direction,
+    })
+}
+
+/// The grid with the beams of `entry` drawn in, or of the best entry without one.
+fn describe(
+    file: &str,
+    table: &ElementTable,
+    entry: Option<&str>,
+    colour: bool,
+) -> Result<String, String> {
+    let contraption = Contraption::parse_with(file, table).map_err(|e| e.to_string())?;
+    let entry = match entry {
+        Some(s) => parse_entry(s, &contraption)?,
+        None => {
+            BeamGraph::new(&contraption)
+                .best_entry(threads_from_args())
+                .0
+        }
+    };
+    Ok(format!(
+        "{}Entry {:?} at {:?} energizes {} tiles\n",
+        contraption.render(entry, colour),
+        entry.direction,
+        entry.position,
+        contraption.energized(entry)
+    ))
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let file_arg = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(|path| std::fs::read_to_string(path).unwrap())
+    };
+    let input = file_arg("--grid").unwrap_or_else(|| include_str!("input.txt").to_string());
+    let table =
+        match file_arg("--elements").map(|spec| ElementTable::default().extend_from_spec(&spec)) {
+            None => ElementTable::default(),
+            Some(Ok(table)) => table,
+            Some(Err(e)) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        };
+    let entry = args
+        .iter()
+        .position(|a| a == "--entry")
+        .and_then(|i| args.get(i + 1));
+    let colour = !args.iter().any(|a| a == "--plain");
+
+    let t0 = Instant::now();
+    match describe(&input, &table, entry.map(String::as_str), colour) {
+        Ok(out) => print!("{}", out),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+    println!("Time: {:?}", t0.elapsed());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = ".|...\\....
+|.-.\\.....
+.....|-...
+........|.
+..........
+.........\\
+..../.\\\\..
+.-.-/..|..
+.|....-|.\\
+..//.|....";
+
+    #[test]
+    fn test_chosen_entry() {
+        let out = describe(INPUT, &ElementTable::default(), Some("0,0,>"), false).unwrap();
+        assert!(out.starts_with(">|<<<\\....\n|v-.\\^....\n"));
+        assert!(out.ends_with("Entry Right at (0, 0) energizes 46 tiles\n"));
+    }
+
+    #[test]
+    fn test_best_entry() {
+        let out = describe(INPUT, &ElementTable::default(), None, false).unwrap();
+        assert!(out.starts_with(".|<2<\\....\n|v-v\\^....\n"));
+        assert!(out.ends_with("Entry Down at (3, 0) energizes 51 tiles\n"));
+    }
+
+    #[test]
+    fn test_custom_elements() {
+        let table = ElementTable::default()
+            .extend_from_spec("# absorber")
+            .unwrap();
+        let out = describe("..#\n...", &table, Some("0,0,>"), false).unwrap();
+        assert_eq!(out, ">>#\n...\nEntry Right at (0, 0) energizes 3 tiles\n");
+        assert_eq!(
+            describe("..#", &ElementTable::default(), None, false),
+            Err("Unknown tile # at (2, 0)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_entry() {
+        assert!(describe(INPUT, &ElementTable::default(), Some("0,0"), false).is_err());
+        assert!(describe(INPUT, &ElementTable::default(), Some("0,0,x"), false).is_err());
+        assert_eq!(
+            describe(INPUT, &ElementTable::default(), Some("10,0,v"), false),
+            Err("Entry 10,0,v outside of the 10x10 grid".to_string())
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use crate::Direction;
+use std::fmt;
+
+/// How one kind of tile treats beams: the directions a beam leaves in for every direction it
+/// comes in heading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    outputs: [Vec<Direction>; 4],
+    /// A beam entering a portal leaves from the other tile with the same glyph instead.
+    portal: bool,
+}
+
+impl Element {
+    pub fn new(outputs: impl Fn(Direction) -> Vec<Direction>) -> Self {
+        Element {
+            outputs: Direction::all().map(outputs),
+            portal: false,
+        }
+    }
+
+    /// `.`: beams pass straight through.
+    pub fn empty() -> Self {
+        Element::new(|d| vec![d])
+    }
+
+    /// `|` or `-`: beams along `along` pass through, others split both ways along it.
+    pub fn splitter(along: [Direction; 2]) -> Self {
+        Element::new(|d| {
+            if along.contains(&d) {
+                vec![d]
+            } else {
+                along.to_vec()
+            }
+        })
+    }
+
+    /// `/`: a beam heading right is turned up.
+    pub fn mirror_up() -> Self {
+        Element::new(|d| {
+            vec![match d {
+                Direction::Up => Direction::Right,
+                Direction::Down => Direction::Left,
+                Direction::Left => Direction::Down,
+                Direction::Right => Direction::Up,
+            }]
+        })
+    }
+
+    /// `\`: a beam heading right is turned down.
+    pub fn mirror_down() -> Self {
+        Element::new(|d| {
+            vec![match d {
+                Direction::Up => Direction::Left,
+                Direction::Down => Direction::Right,
+                Direction::Left => Direction::Up,
+                Direction::Right => Direction::Down,
+            }]
+        })
+    }
+
+    /// Stops every beam.
+    pub fn absorber() -> Self {
+        Element::new(|_| vec![])
+    }
+
+    /// Lets beams heading `direction` through and stops all others.
+    pub fn diode(direction: Direction) -> Self {
+        Element::new(|d| if d == direction { vec![d] } else { vec![] })
+    }
+
+    /// Splits a beam three ways: straight on and turned to either side.
+    pub fn prism() -> Self {
+        Element::new(|d| vec![d.turn_left(), d, d.turn_right()])
+    }
+
+    /// Beams pass through, but leave from the paired portal tile.
+    pub fn portal() -> Self {
+        Element {
+            portal: true,
+            ..Element::empty()
+        }
+    }
+
+    pub fn outputs(&self, direction: Direction) -> &[Direction] {
+        &self.outputs[direction as usize]
+    }
+
+    pub fn is_portal(&self) -> bool {
+        self.portal
+    }
+
+    /// Whether beams cross the tile untouched, so the render can draw them on it.
+    pub fn is_empty(&self) -> bool {
+        !self.portal && *self == Element::empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementError {
+    UnknownTile { glyph: char, at: (usize, usize) },
+    UnpairedPortal { glyph: char, count: usize },
+    InvalidSpec { line: usize, spec: String },
+}
+
+impl fmt::Display for ElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElementError::UnknownTile { glyph, at } => {
+                write!(f, "Unknown tile {} at {:?}", glyph, at)
+            }
+            ElementError::UnpairedPortal { glyph, count } => write!(
+                f,
+                "Portal {} appears {} times, it needs exactly 2",
+                glyph, count
+            ),
+            ElementError::InvalidSpec { line, spec } => {
+                write!(f, "Invalid element on line {}: {}", line, spec)
+            }
+        }
+    }
+}
+
+/// The glyph to element mapping a contraption is parsed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementTable {
+    elements: Vec<(char, Element)>,
+}
+
+/// The puzzle's five tiles.
+impl Default for ElementTable {
+    fn default() -> Self {
+        ElementTable::new()
+            .with('.', Element::empty())
+            .with('|', Element::splitter([Direction::Up, Direction::Down]))
+            .with('-', Element::splitter([Direction::Left, Direction::Right]))
+            .with('/', Element::mirror_up())
+            .with('\\', Element::mirror_down())
+    }
+}
+
+impl ElementTable {
+    /// A table without any elements.
+    pub fn new() -> Self {
+        ElementTable { elements: vec![] }
+    }
+
+    /// Adds `glyph`, replacing the element it had.
+    pub fn with(mut self, glyph: char, element: Element) -> Self {
+        match self.index(glyph) {
+            Some(i) => self.elements[i].1 = element,
+            None => self.elements.push((glyph, element)),
+        }
+        self
+    }
+
+    pub fn index(&self, glyph: char) -> Option<usize> {
+        self.elements.iter().position(|(g, _)| *g == glyph)
+    }
+
+    pub fn glyph(&self, index: usize) -> char {
+        self.elements[index].0
+    }
+
+    pub fn element(&self, index: usize) -> &Element {
+        &self.elements[index].1
+    }
+
+    /// Adds the elements of a spec to the table, one `glyph kind` per line, where the kind is
+    /// one of `empty`, `split-vertical`, `split-horizontal`, `mirror-up`, `mirror-down`,
+    /// `absorber`, `diode <arrow>`, `prism` or `portal`. Blank lines are skipped.
+    pub fn extend_from_spec(mut self, spec: &str) -> Result<Self, ElementError> {
+        for (i, line) in spec.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let invalid = || ElementError::InvalidSpec {
+                line: i + 1,
+                spec: line.to_string(),
+            };
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let mut glyph = words[0].chars();
+            let (Some(glyph), None) = (glyph.next(), glyph.next()) else {
+                return Err(invalid());
+            };
+            let element = match words[1..] {
+                ["empty"] => Element::empty(),
+                ["split-vertical"] => Element::splitter([Direction::Up, Direction::Down]),
+                ["split-horizontal"] => Element::splitter([Direction::Left, Direction::Right]),
+                ["mirror-up"] => Element::mirror_up(),
+                ["mirror-down"] => Element::mirror_down(),
+                ["absorber"] => Element::absorber(),
+                ["diode", arrow] => {
+                    let mut arrow = arrow.chars();
+                    match (arrow.next().and_then(Direction::from_glyph), arrow.next()) {
+                        (Some(direction), None) => Element::diode(direction),
+                        _ => return Err(invalid()),
+                    }
+                }
+                ["prism"] => Element::prism(),
+                ["portal"] => Element::portal(),
+                _ => return Err(invalid()),
+            };
+            self = self.with(glyph, element);
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec() {
+        let table = ElementTable::default()
+            .extend_from_spec("# absorber\n\n> diode >\n* prism\nA portal\n")
+            .unwrap();
+        let element = |glyph| table.element(table.index(glyph).unwrap());
+        assert_eq!(element('#').outputs(Direction::Up), &[]);
+        assert_eq!(element('>').outputs(Direction::Right), &[Direction::Right]);
+        assert_eq!(element('>').outputs(Direction::Left), &[]);
+        assert_eq!(
+            element('*').outputs(Direction::Up),
+            &[Direction::Left, Direction::Up, Direction::Right]
+        );
+        assert!(element('A').is_portal());
+        assert!(element('.').is_empty() && !element('A').is_empty());
+
+        assert_eq!(
+            ElementTable::new().extend_from_spec("> diode x"),
+            Err(ElementError::InvalidSpec {
+                line: 1,
+                spec: "> diode x".to_string()
+            })
+        );
+        assert!(ElementTable::new().extend_from_spec("ab prism").is_err());
+    }
+
+    #[test]
+    fn test_replacing_an_element() {
+        // A mirror can be turned into an absorber, without the table growing
+        let table = ElementTable::default().with('/', Element::absorber());
+        assert_eq!(table.index('/'), Some(3));
+        assert_eq!(table.element(3), &Element::absorber());
+        assert_eq!(table.index('#'), None);
+    }
+}
@@ -0,0 +1,633 @@
+use colored::Colorize;
+use elements::{ElementError, ElementTable};
+use std::collections::HashMap;
+
+pub mod elements;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn all() -> [Direction; 4] {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+    }
+
+    pub fn to_vec(self) -> (i64, i64) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Left => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Right => Direction::Up,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.turn_left().opposite()
+    }
+
+    /// The arrow the puzzle draws for a beam heading this way.
+    pub fn glyph(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+
+    pub fn from_glyph(c: char) -> Option<Self> {
+        Direction::all().into_iter().find(|d| d.glyph() == c)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A beam on the tile at `position`, heading `direction` before the tile has turned it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Beam {
+    pub position: (usize, usize),
+    pub direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contraption {
+    /// Every tile as its index in `table`.
+    grid: Vec<Vec<usize>>,
+    table: ElementTable,
+    /// Every portal tile and the tile its beams leave from.
+    partners: HashMap<(usize, usize), (usize, usize)>,
+}
+
+impl Contraption {
+    /// The puzzle's contraption, made of the default elements.
+    pub fn parse(file: &str) -> Self {
+        Contraption::parse_with(file, &ElementTable::default()).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn parse_with(file: &str, table: &ElementTable) -> Result<Self, ElementError> {
+        let mut grid = vec![];
+        let mut portals: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
+        for (y, line) in file.lines().enumerate() {
+            let mut row = vec![];
+            for (x, glyph) in line.chars().enumerate() {
+                let index = table
+                    .index(glyph)
+                    .ok_or(ElementError::UnknownTile { glyph, at: (x, y) })?;
+                if table.element(index).is_portal() {
+                    portals.entry(glyph).or_default().push((x, y));
+                }
+                row.push(index);
+            }
+            grid.push(row);
+        }
+
+        let mut partners = HashMap::new();
+        for (glyph, tiles) in portals {
+            let [a, b] = tiles[..] else {
+                return Err(ElementError::UnpairedPortal {
+                    glyph,
+                    count: tiles.len(),
+                });
+            };
+            partners.insert(a, b);
+            partners.insert(b, a);
+        }
+        Ok(Contraption {
+            grid,
+            table: table.clone(),
+            partners,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// Every beam state as one number, four per tile in row order.
+    fn node(&self, beam: Beam) -> usize {
+        let (x, y) = beam.position;
+        (y * self.width() + x) * 4 + beam.direction.index()
+    }
+
+    fn beam(&self, node: usize) -> Beam {
+        let tile = node / 4;
+        Beam {
+            position: (tile % self.width(), tile / self.width()),
+            direction: Direction::all()[node % 4],
+        }
+    }
+
+    /// The beams this one turns into on the next tiles, leaving out those that exit the grid.
+    /// Beams entering a portal carry on from its partner.
+    pub fn next_beams(&self, beam: Beam) -> Vec<Beam> {
+        let element = self
+            .table
+            .element(self.grid[beam.position.1][beam.position.0]);
+        let (x, y) = match element.is_portal() {
+            true => self.partners[&beam.position],
+            false => beam.position,
+        };
+        element
+            .outputs(beam.direction)
+            .iter()
+            .filter_map(|&direction| {
+                let (dx, dy) = direction.to_vec();
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                if nx < 0 || ny < 0 || nx >= self.width() as i64 || ny >= self.height() as i64 {
+                    return None;
+                }
+                Some(Beam {
+                    position: (nx as usize, ny as usize),
+                    direction,
+                })
+            })
+            .collect()
+    }
+
+    /// Every beam coming in from the edge: down from the top, up from the bottom, right from
+    /// the left and left from the right.
+    pub fn entries(&self) -> Vec<Beam> {
+        let (width, height) = (self.width(), self.height());
+        let vertical = (0..width).flat_map(|x| {
+            [
+                Beam {
+                    position: (x, 0),
+                    direction: Direction::Down,
+                },
+                Beam {
+                    position: (x, height - 1),
+                    direction: Direction::Up,
+                },
+            ]
+        });
+        let horizontal = (0..height).flat_map(|y| {
+            [
+                Beam {
+                    position: (0, y),
+                    direction: Direction::Right,
+                },
+                Beam {
+                    position: (width - 1, y),
+                    direction: Direction::Left,
+                },
+            ]
+        });
+        vertical.chain(horizontal).collect()
+    }
+
+    /// Every beam state reached from `entry`, indexed by `node`.
+    fn reached(&self, entry: Beam) -> Vec<bool> {
+        let mut seen = vec![false; self.width() * self.height() * 4];
+        let mut beams = vec![entry];
+        seen[self.node(entry)] = true;
+        while let Some(beam) = beams.pop() {
+            for next in self.next_beams(beam) {
+                let node = self.node(next);
+                if !seen[node] {
+                    seen[node] = true;
+                    beams.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    /// Number of tiles energized by `entry`, following every beam state it reaches.
+    pub fn energized(&self, entry: Beam) -> usize {
+        self.reached(entry)
+            .chunks(4)
+            .filter(|tile| tile.contains(&true))
+            .count()
+    }
+
+    /// The directions beams cross every tile in, starting from `entry`.
+    pub fn beam_directions(&self, entry: Beam) -> Vec<Vec<Vec<Direction>>> {
+        let reached = self.reached(entry);
+        let mut directions = vec![vec![vec![]; self.width()]; self.height()];
+        for (node, _) in reached.iter().enumerate().filter(|(_, r)| **r) {
+            let beam = self.beam(node);
+            let (x, y) = beam.position;
+            directions[y][x].push(beam.direction);
+        }
+        directions
+    }
+
+    /// The grid with the beams from `entry` drawn in like the puzzle does: an arrow on an empty
+    /// tile one beam crosses, or the number of beams when more do. With `colour` the energized
+    /// tiles are highlighted as well.
+    pub fn render(&self, entry: Beam, colour: bool) -> String {
+        let mut out = String::new();
+        for (row, directions) in self.grid.iter().zip(self.beam_directions(entry)) {
+            for (&index, directions) in row.iter().zip(directions) {
+                let empty = self.table.element(index).is_empty();
+                let glyph = match &directions[..] {
+                    [direction] if empty => direction.glyph().to_string(),
+                    [_, _, ..] if empty => directions.len().to_string(),
+                    _ => self.table.glyph(index).to_string(),
+                };
+                if colour && !directions.is_empty() {
+                    out += &glyph.yellow().bold().to_string();
+                } else {
+                    out += &glyph;
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The entry that energizes the most tiles, trying each one from scratch.
+    pub fn best_entry_brute(&self, threads: usize) -> (Beam, usize) {
+        let entries = self.entries();
+        let counts = par_map(&entries, threads, |entry| self.energized(*entry));
+        best(&entries, &counts)
+    }
+}
+
+/// A set of tiles as one bit per tile.
+#[derive(Debug, Clone)]
+struct TileSet(Vec<u64>);
+
+impl TileSet {
+    fn new(tiles: usize) -> Self {
+        TileSet(vec![0; tiles.div_ceil(64)])
+    }
+
+    fn insert(&mut self, tile: usize) {
+        self.0[tile / 64] |= 1 << (tile % 64);
+    }
+
+    fn union_with(&mut self, other: &TileSet) {
+        for (word, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *word |= other;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// Tarjan's algorithm without recursion. Returns the component of every node and how many
+/// there are, numbered so that edges only lead to the same or a lower component.
+fn strongly_connected(succ: &[Vec<usize>]) -> (Vec<usize>, usize) {
+    const UNSEEN: usize = usize::MAX;
+    let n = succ.len();
+    let mut index = vec![UNSEEN; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut component = vec![UNSEEN; n];
+    let mut count = 0;
+    let mut next_index = 0;
+
+    for root in 0..n {
+        if index[root] != UNSEEN {
+            continue;
+        }
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        // Each call frame is a node and how many of its edges have been followed
+        let mut calls = vec![(root, 0)];
+        while let Some((v, i)) = calls.last_mut() {
+            let v = *v;
+            if let Some(&w) = succ[v].get(*i) {
+                *i += 1;
+                if index[w] == UNSEEN {
+                    index[w] = next_index;
+                    low[w] = next_index;
+                    next_index += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(parent, _)) = calls.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    component[w] = count;
+                    if w == v {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+        }
+    }
+    (component, count)
+}
+
+/// The beam states of a contraption condensed into strongly connected components, with the
+/// number of tiles energized from each component.
+///
+/// Every state of a component reaches the same tiles, so the tiles of a component are its own
+/// plus those of the components it leads to. Those are built from the sinks up, and a set is
+/// only kept until the last component leading into it has used it.
+#[derive(Debug, Clone)]
+pub struct BeamGraph {
+    contraption: Contraption,
+    component: Vec<usize>,
+    energized: Vec<usize>,
+}
+
+impl BeamGraph {
+    pub fn new(contraption: &Contraption) -> Self {
+        let nodes = contraption.width() * contraption.height() * 4;
+        let succ = (0..nodes)
+            .map(|node| {
+                contraption
+                    .next_beams(contraption.beam(node))
+                    .into_iter()
+                    .map(|beam| contraption.node(beam))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let (component, count) = strongly_connected(&succ);
+
+        let mut members = vec![vec![]; count];
+        for (node, &c) in component.iter().enumerate() {
+            members[c].push(node);
+        }
+        let mut leads_to = vec![vec![]; count];
+        let mut pending = vec![0; count];
+        for (c, nodes) in members.iter().enumerate() {
+            let mut next = nodes
+                .iter()
+                .flat_map(|&node| succ[node].iter().map(|&s| component[s]))
+                .filter(|&s| s != c)
+                .collect::<Vec<_>>();
+            next.sort_unstable();
+            next.dedup();
+            for &s in &next {
+                pending[s] += 1;
+            }
+            leads_to[c] = next;
+        }
+
+        let tiles = contraption.width() * contraption.height();
+        let mut sets: Vec<Option<TileSet>> = vec![None; count];
+        let mut energized = vec![0; count];
+        for c in 0..count {
+            let mut set = TileSet::new(tiles);
+            for &node in &members[c] {
+                set.insert(node / 4);
+            }
+            for &s in &leads_to[c] {
+                set.union_with(sets[s].as_ref().unwrap());
+                pending[s] -= 1;
+                if pending[s] == 0 {
+                    sets[s] = None;
+                }
+            }
+            energized[c] = set.len();
+            if pending[c] > 0 {
+                sets[c] = Some(set);
+            }
+        }
+
+        BeamGraph {
+            contraption: contraption.clone(),
+            component,
+            energized,
+        }
+    }
+
+    /// Number of tiles energized by `entry`, looked up from its component.
+    pub fn energized(&self, entry: Beam) -> usize {
+        self.energized[self.component[self.contraption.node(entry)]]
+    }
+
+    /// The entry that energizes the most tiles, with the entries split over `threads` workers.
+    pub fn best_entry(&self, threads: usize) -> (Beam, usize) {
+        let entries = self.contraption.entries();
+        let counts = par_map(&entries, threads, |entry| self.energized(*entry));
+        best(&entries, &counts)
+    }
+}
+
+/// The first entry with the highest count.
+fn best(entries: &[Beam], counts: &[usize]) -> (Beam, usize) {
+    entries
+        .iter()
+        .zip(counts.iter())
+        .fold(
+            None,
+            |best: Option<(Beam, usize)>, (&entry, &count)| match best {
+                Some((_, most)) if most >= count => best,
+                _ => Some((entry, count)),
+            },
+        )
+        .expect("Contraption without entries")
+}
+
+/// Applies `f` to every item, in chunks over `threads` scoped threads, keeping the order.
+pub fn par_map<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let chunk_size = items.len().div_ceil(threads.max(1)).max(1);
+    std::thread::scope(|scope| {
+        let workers = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+/// The worker count from `--threads`, or as many as the machine has.
+pub fn threads_from_args() -> usize {
+    let args = std::env::args().collect::<Vec<_>>();
+    args.iter()
+        .position(|a| a == "--threads")
+        .and_then(|i| args.get(i + 1))
+        .map(|n| n.parse().unwrap())
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = ".|...\\....
+|.-.\\.....
+.....|-...
+........|.
+..........
+.........\\
+..../.\\\\..
+.-.-/..|..
+.|....-|.\\
+..//.|....";
+
+    #[test]
+    fn test_energized() {
+        let contraption = Contraption::parse(INPUT);
+        let entry = Beam {
+            position: (0, 0),
+            direction: Direction::Right,
+        };
+        assert_eq!(contraption.energized(entry), 46);
+        assert_eq!(BeamGraph::new(&contraption).energized(entry), 46);
+    }
+
+    #[test]
+    fn test_graph_matches_brute_force() {
+        // The example, and one where splitters send beams round in loops
+        for input in [INPUT, "-.\\.|\n.|.-.\n\\.-./\n./.|\\\n|-\\.."] {
+            let contraption = Contraption::parse(input);
+            let graph = BeamGraph::new(&contraption);
+            for entry in contraption.entries() {
+                assert_eq!(
+                    graph.energized(entry),
+                    contraption.energized(entry),
+                    "{:?}",
+                    entry
+                );
+            }
+            assert_eq!(graph.best_entry(3), contraption.best_entry_brute(3));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let contraption = Contraption::parse(INPUT);
+        let entry = Beam {
+            position: (0, 0),
+            direction: Direction::Right,
+        };
+        assert_eq!(
+            contraption.render(entry, false),
+            ">|<<<\\....
+|v-.\\^....
+.v...|->>>
+.v...v^.|.
+.v...v^...
+.v...v^..\\
+.v../2\\\\..
+<->-/vv|..
+.|<<<2-|.\\
+.v//.|.v..
+"
+        );
+        let coloured = contraption.render(entry, true);
+        assert!(coloured.starts_with(&">".yellow().bold().to_string()));
+    }
+
+    #[test]
+    fn test_direction_glyphs() {
+        for direction in Direction::all() {
+            assert_eq!(Direction::from_glyph(direction.glyph()), Some(direction));
+        }
+        assert_eq!(Direction::from_glyph('x'), None);
+    }
+
+    #[test]
+    fn test_custom_elements() {
+        let table = ElementTable::default()
+            .extend_from_spec("# absorber\n> diode >\n< diode <\n* prism\nA portal")
+            .unwrap();
+        let entry = Beam {
+            position: (0, 0),
+            direction: Direction::Right,
+        };
+        let render = |grid| {
+            Contraption::parse_with(grid, &table)
+                .unwrap()
+                .render(entry, false)
+        };
+        // Stopped by the absorber, and by the diode facing the other way
+        assert_eq!(render("..#..\n....."), ">>#..\n.....\n");
+        assert_eq!(render("\\....\n.....\n\\>..<"), "\\....\nv....\n\\>>><\n");
+        // The prism sends the beam on and to both sides
+        assert_eq!(render("..*..\n....."), ">>*>>\n..v..\n");
+        // The portal carries the beam on from its partner
+        assert_eq!(render(".A...\n...A."), ">A...\n...A>\n");
+
+        assert_eq!(
+            Contraption::parse_with(".A.A.A", &table),
+            Err(ElementError::UnpairedPortal {
+                glyph: 'A',
+                count: 3
+            })
+        );
+        assert_eq!(
+            Contraption::parse_with("..\n.?", &table),
+            Err(ElementError::UnknownTile {
+                glyph: '?',
+                at: (1, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn test_graph_matches_brute_force_with_custom_elements() {
+        // Portals and prisms make loops no puzzle tile can
+        let table = ElementTable::default()
+            .extend_from_spec("# absorber\n< diode <\n* prism\nA portal\nB portal")
+            .unwrap();
+        let contraption =
+            Contraption::parse_with("A.\\*.|\n.#.-.B\n*..<A/\n/-B..*\n..\\|.<", &table).unwrap();
+        let graph = BeamGraph::new(&contraption);
+        for entry in contraption.entries() {
+            assert_eq!(graph.energized(entry), contraption.energized(entry));
+        }
+    }
+
+    #[test]
+    fn test_strongly_connected() {
+        // 0 -> 1 -> 2 -> 0 is a cycle that leads on to 3
+        let (component, count) = strongly_connected(&[vec![1], vec![2], vec![0, 3], vec![]]);
+        assert_eq!(count, 2);
+        assert_eq!(component, vec![1, 1, 1, 0]);
+    }
+}
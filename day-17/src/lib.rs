@@ -0,0 +1,387 @@
+use colored::Colorize;
+use std::{cmp::Reverse, collections::BinaryHeap, fmt};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    pub fn all() -> [Direction; 4] {
+        [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ]
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    pub fn to_vec(self) -> (i64, i64) {
+        match self {
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouteError {
+    /// The crucible can never turn, or can never move.
+    InvalidRuns {
+        min_run: usize,
+        max_run: usize,
+    },
+    OutOfBounds {
+        point: (usize, usize),
+    },
+    NoRoute,
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::InvalidRuns { min_run, max_run } => write!(
+                f,
+                "Runs of {} to {} blocks are not possible, it needs 1 <= min <= max",
+                min_run, max_run
+            ),
+            RouteError::OutOfBounds { point } => write!(f, "{:?} is outside of the map", point),
+            RouteError::NoRoute => write!(f, "No route to the goal"),
+        }
+    }
+}
+
+/// The least heat lost on the way to the goal and the blocks passed, start included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub heat_loss: usize,
+    pub path: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeatMap {
+    grid: Vec<Vec<usize>>,
+}
+
+impl HeatMap {
+    pub fn parse(file: &str) -> Self {
+        HeatMap {
+            grid: file
+                .lines()
+                .map(|line| {
+                    line.chars()
+                        .map(|c| c.to_digit(10).unwrap() as usize)
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// The bottom right block, where the puzzle's crucibles go.
+    pub fn corner(&self) -> (usize, usize) {
+        (self.width() - 1, self.height() - 1)
+    }
+
+    /// Dijkstra over (position, direction, run length) states, where the run is how many blocks
+    /// the crucible has gone straight. It turns only after `min_run` blocks, goes at most
+    /// `max_run` blocks straight, and has to have done `min_run` to stop at the goal.
+    pub fn best_route(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        min_run: usize,
+        max_run: usize,
+    ) -> Result<Route, RouteError> {
+        if min_run == 0 || min_run > max_run {
+            return Err(RouteError::InvalidRuns { min_run, max_run });
+        }
+        for point in [start, goal] {
+            if point.0 >= self.width() || point.1 >= self.height() {
+                return Err(RouteError::OutOfBounds { point });
+            }
+        }
+
+        let runs = max_run + 1;
+        let state = |(x, y): (usize, usize), dir: Direction, run: usize| {
+            ((y * self.width() + x) * 4 + dir as usize) * runs + run
+        };
+        let unstate = |s: usize| {
+            let (tile, run) = (s / runs / 4, s % runs);
+            let dir = Direction::all()[s / runs % 4];
+            ((tile % self.width(), tile / self.width()), dir, run)
+        };
+
+        // A run of 0 is the crucible standing at the start, free to head anywhere
+        let states = self.width() * self.height() * 4 * runs;
+        let mut cost = vec![usize::MAX; states];
+        let mut previous = vec![usize::MAX; states];
+        let mut queue = BinaryHeap::new();
+        let first = state(start, Direction::Right, 0);
+        cost[first] = 0;
+        queue.push(Reverse((0, first)));
+
+        while let Some(Reverse((heat_loss, s))) = queue.pop() {
+            if heat_loss > cost[s] {
+                continue;
+            }
+            let (position, dir, run) = unstate(s);
+            if position == goal && (run >= min_run || position == start) {
+                let mut path = vec![];
+                let mut at = s;
+                while at != usize::MAX {
+                    path.push(unstate(at).0);
+                    at = previous[at];
+                }
+                path.reverse();
+                return Ok(Route { heat_loss, path });
+            }
+
+            for next_dir in Direction::all() {
+                let next_run = match (run, next_dir == dir) {
+                    (0, _) => 1,
+                    (_, true) if run < max_run => run + 1,
+                    (_, false) if run >= min_run && next_dir != dir.opposite() => 1,
+                    _ => continue,
+                };
+                let (dx, dy) = next_dir.to_vec();
+                let (nx, ny) = (position.0 as i64 + dx, position.1 as i64 + dy);
+                if nx < 0 || ny < 0 || nx >= self.width() as i64 || ny >= self.height() as i64 {
+                    continue;
+                }
+                let next_position = (nx as usize, ny as usize);
+                let next = state(next_position, next_dir, next_run);
+                let next_cost = heat_loss + self.grid[next_position.1][next_position.0];
+                if next_cost < cost[next] {
+                    cost[next] = next_cost;
+                    previous[next] = s;
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+        Err(RouteError::NoRoute)
+    }
+
+    /// The map with the blocks of `path` in green, or marked with `#` without `colour`.
+    pub fn render(&self, path: &[(usize, usize)], colour: bool) -> String {
+        let mut out = String::new();
+        for (y, row) in self.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                match (path.contains(&(x, y)), colour) {
+                    (true, true) => out += &cell.to_string().green().to_string(),
+                    (true, false) => out.push('#'),
+                    _ => out += &cell.to_string(),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// What to solve, from the command line: `--min-run`, `--max-run`, `--start x,y`, `--goal x,y`
+/// and `--show-path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub min_run: usize,
+    pub max_run: usize,
+    pub start: Option<(usize, usize)>,
+    pub goal: Option<(usize, usize)>,
+    pub show_path: bool,
+}
+
+impl Options {
+    /// From the top left to the bottom right, without the path.
+    pub fn new(min_run: usize, max_run: usize) -> Self {
+        Options {
+            min_run,
+            max_run,
+            start: None,
+            goal: None,
+            show_path: false,
+        }
+    }
+
+    /// The options given, with the runs defaulting to those of the part.
+    pub fn from_args(min_run: usize, max_run: usize) -> Self {
+        let args = std::env::args().collect::<Vec<_>>();
+        let value = |flag: &str| {
+            args.iter()
+                .position(|a| a == flag)
+                .and_then(|i| args.get(i + 1))
+        };
+        let point = |flag: &str| {
+            value(flag).map(|p| {
+                let (x, y) = p.split_once(',').unwrap();
+                (x.trim().parse().unwrap(), y.trim().parse().unwrap())
+            })
+        };
+        Options {
+            min_run: value("--min-run").map_or(min_run, |n| n.parse().unwrap()),
+            max_run: value("--max-run").map_or(max_run, |n| n.parse().unwrap()),
+            start: point("--start"),
+            goal: point("--goal"),
+            show_path: args.iter().any(|a| a == "--show-path"),
+        }
+    }
+
+    /// The best route from the start, or the top left block, to the goal, or the bottom right.
+    pub fn route(&self, map: &HeatMap) -> Result<Route, RouteError> {
+        map.best_route(
+            self.start.unwrap_or((0, 0)),
+            self.goal.unwrap_or(map.corner()),
+            self.min_run,
+            self.max_run,
+        )
+    }
+}
+
+/// The path as coordinates and the map with it drawn in, for `--show-path`.
+pub fn describe_path(map: &HeatMap, route: &Route) -> String {
+    let steps = route
+        .path
+        .iter()
+        .map(|(x, y)| format!("({},{})", x, y))
+        .collect::<Vec<_>>();
+    format!(
+        "Path: {}\n{}",
+        steps.join(" -> "),
+        map.render(&route.path, true)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    /// Checks that the path is a route the crucible may take and loses the heat it claims.
+    fn check_route(map: &HeatMap, route: &Route, min_run: usize, max_run: usize) {
+        let steps = route
+            .path
+            .windows(2)
+            .map(|w| {
+                let (dx, dy) = (w[1].0 as i64 - w[0].0 as i64, w[1].1 as i64 - w[0].1 as i64);
+                Direction::all()
+                    .into_iter()
+                    .find(|d| d.to_vec() == (dx, dy))
+                    .expect("Path is not connected")
+            })
+            .collect::<Vec<_>>();
+        let mut runs = vec![];
+        for (i, dir) in steps.iter().enumerate() {
+            if i > 0 && steps[i - 1] == *dir {
+                *runs.last_mut().unwrap() += 1;
+            } else {
+                assert!(i == 0 || steps[i - 1] != dir.opposite(), "Turned back");
+                runs.push(1);
+            }
+        }
+        assert!(runs.iter().all(|r| (min_run..=max_run).contains(r)));
+        let heat_loss = route.path[1..]
+            .iter()
+            .map(|(x, y)| map.grid[*y][*x])
+            .sum::<usize>();
+        assert_eq!(heat_loss, route.heat_loss);
+    }
+
+    #[test]
+    fn test_crucibles() {
+        let map = HeatMap::parse(INPUT);
+        for (min_run, max_run, heat_loss) in [(1, 3, 102), (4, 10, 94)] {
+            let route = map
+                .best_route((0, 0), map.corner(), min_run, max_run)
+                .unwrap();
+            assert_eq!(route.heat_loss, heat_loss);
+            assert_eq!(route.path[0], (0, 0));
+            assert_eq!(*route.path.last().unwrap(), map.corner());
+            check_route(&map, &route, min_run, max_run);
+        }
+    }
+
+    #[test]
+    fn test_ultra_crucible_has_to_run_to_the_end() {
+        let map = HeatMap::parse(
+            "111111111111
+999999999991
+999999999991
+999999999991
+999999999991",
+        );
+        let route = map.best_route((0, 0), map.corner(), 4, 10).unwrap();
+        assert_eq!(route.heat_loss, 71);
+        check_route(&map, &route, 4, 10);
+    }
+
+    #[test]
+    fn test_start_and_goal() {
+        let map = HeatMap::parse(INPUT);
+        // Backwards the start block is paid for instead of the goal
+        let back = map.best_route(map.corner(), (0, 0), 1, 3).unwrap();
+        assert_eq!(back.heat_loss, 102 - 3 + 2);
+        check_route(&map, &back, 1, 3);
+
+        let here = map.best_route((4, 4), (4, 4), 1, 3).unwrap();
+        assert_eq!(here.path, vec![(4, 4)]);
+        assert_eq!(here.heat_loss, 0);
+
+        assert_eq!(
+            map.best_route((0, 0), (13, 0), 1, 3),
+            Err(RouteError::OutOfBounds { point: (13, 0) })
+        );
+        assert_eq!(
+            map.best_route((0, 0), (1, 1), 4, 3),
+            Err(RouteError::InvalidRuns {
+                min_run: 4,
+                max_run: 3
+            })
+        );
+        // Too small to ever get four blocks straight before the goal
+        assert_eq!(
+            HeatMap::parse("12\n34").best_route((0, 0), (1, 1), 4, 10),
+            Err(RouteError::NoRoute)
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let map = HeatMap::parse("123\n456");
+        assert_eq!(map.render(&[(0, 0), (1, 0), (1, 1)], false), "##3\n4#6\n");
+        let route = map.best_route((0, 0), (2, 1), 1, 3).unwrap();
+        assert!(describe_path(&map, &route).starts_with("Path: (0,0) -> (1,0) -> (2,0) -> (2,1)\n"));
+    }
+}
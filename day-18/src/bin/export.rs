@@ -0,0 +1,57 @@
+use day_18::{parse_plan, preview, svg, Interpretation, PlanError};
+
+/// The preview of the plan and, given a path, the SVG written there.
+fn export(
+    file: &str,
+    interpretation: Interpretation,
+    size: (usize, usize),
+) -> Result<(String, String), PlanError> {
+    let steps = parse_plan(file, interpretation)?;
+    Ok((preview(&steps, size.0, size.1), svg(&steps)))
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+    let args = std::env::args().collect::<Vec<_>>();
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+    let interpretation = match args.iter().any(|a| a == "--decoded") {
+        true => Interpretation::Decoded,
+        false => Interpretation::Plain,
+    };
+    let size = value("--preview").map_or((80, 40), |s| {
+        let (w, h) = s.split_once('x').expect("Preview size as WIDTHxHEIGHT");
+        (w.parse().unwrap(), h.parse().unwrap())
+    });
+
+    match export(input, interpretation, size) {
+        Ok((preview, svg)) => {
+            print!("{}", preview);
+            if let Some(path) = value("--svg") {
+                std::fs::write(path, svg).unwrap();
+                println!("Wrote {}", path);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export() {
+        let input = "R 2 (#ff0000)\nD 2 (#00ff00)\nL 2 (#0000ff)\nU 2 (#123456)";
+        let (preview, svg) = export(input, Interpretation::Plain, (80, 40)).unwrap();
+        assert_eq!(preview, "###\n#:#\n###\n");
+        assert!(svg.contains("stroke=\"#0000ff\""));
+        assert!(export(input, Interpretation::Decoded, (80, 40)).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use day_18::{validate, Colour, DigStep, PlanError};
 use std::time::Instant;
 
 /* Small illustration of the problem
@@ -47,7 +48,7 @@ impl Direction {
         }
     }
 
-    fn to_vec(&self) -> (i64, i64) {
+    fn to_vec(self) -> (i64, i64) {
         match self {
             Direction::Up => (0, -1),
             Direction::Down => (0, 1),
@@ -64,7 +65,7 @@ struct Edge {
     dir: Direction,
 }
 
-fn count_internal_at_height(height: i64, vertical: &Vec<Edge>, horizontal: &Vec<Edge>) -> i64 {
+fn count_internal_at_height(height: i64, vertical: &[Edge], horizontal: &[Edge]) -> i64 {
     let cur_horizontal_edges = horizontal
         .iter()
         .filter(|e| e.start.1 == height)
@@ -115,9 +116,10 @@ fn count_internal_at_height(height: i64, vertical: &Vec<Edge>, horizontal: &Vec<
     ranges.iter().map(|r| r.end - r.start).sum()
 }
 
-fn solve(file: &str) -> i64 {
+fn solve(file: &str) -> Result<i64, PlanError> {
     let mut pos = (0, 0);
     let mut edges = vec![];
+    let mut steps = vec![];
     let mut count = 0;
 
     file.lines().for_each(|line| {
@@ -128,7 +130,12 @@ fn solve(file: &str) -> i64 {
         // let dist = i64::from_str_radix(&hex, 16).unwrap();
 
         let dir = Direction::from_str(parts[0]).unwrap();
-        let dist = parts[1].parse::<i64>().unwrap();
+        let dist = parts[1].parse::<u32>().unwrap() as i64;
+        steps.push(DigStep {
+            dir: day_18::Direction::from_letter(parts[0]).unwrap(),
+            dist,
+            colour: Colour(0),
+        });
 
         let orientation = match dir {
             Direction::Up | Direction::Down => Orientation::Vertical,
@@ -161,8 +168,8 @@ fn solve(file: &str) -> i64 {
         pos = new_pos;
     });
 
-    println!("Final pos: {:?}", pos);
-    assert_eq!(pos, (0, 0));
+    // The sweep below only makes sense for a simple closed loop
+    validate(&steps).map_err(|issues| PlanError::Invalid { issues })?;
     println! {"N_edge: {}, total_dist: {}", edges.len(), count};
 
     let (mut horizontal, mut vertical): (Vec<Edge>, Vec<Edge>) =
@@ -171,8 +178,8 @@ fn solve(file: &str) -> i64 {
             Direction::Left | Direction::Right => true,
         });
 
-    horizontal.sort_by(|a, b| a.start.1.cmp(&b.start.1));
-    vertical.sort_by(|a, b| a.start.0.cmp(&b.start.0));
+    horizontal.sort_by_key(|e| e.start.1);
+    vertical.sort_by_key(|e| e.start.0);
 
     let mut relevant_heights = horizontal.iter().map(|e| e.start.1).collect::<Vec<_>>();
     relevant_heights.dedup();
@@ -188,14 +195,20 @@ fn solve(file: &str) -> i64 {
         let add = count_internal_at_height(y, &vertical, &horizontal);
         count += add;
     }
-    count
+    Ok(count)
 }
 
 fn main() {
     let input = include_str!("input.txt");
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = match solve(input) {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let duration = t0.elapsed();
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
@@ -221,27 +234,33 @@ R 2 (#7807d2)
 U 3 (#a77fa3)
 L 2 (#015232)
 U 2 (#7a21e3)";
-        assert_eq!(solve(input), 62);
+        assert_eq!(solve(input), Ok(62));
     }
     #[test]
     fn test_custom_case_1() {
         let input = "R 2 aaa\nD 2 aaa\nR 2 aaa\nU 2 aaa\nR 2 aaa\nD 3 aaa\nL 6 aaa\nU 3 aaa";
-        assert_eq!(solve(input), 26);
+        assert_eq!(solve(input), Ok(26));
     }
 
     #[test]
     fn test_custom_case_3() {
         let input = "R 2 aaa\nU 2 aaa\nR 2 aaa\nD 2 aaa\nR 2 aaa\nU 3 aaa\nL 6 aaa\nD 3 aaa";
-        assert_eq!(solve(input), 26);
+        assert_eq!(solve(input), Ok(26));
     }
     #[test]
     fn test_custom_case_2() {
         let input = "R 9 aaa\nD 8 aaa\nR 3 aaa\nD 7 aaa\nL 4 aaa\nU 4 aaa\nL 4 aaa\nD 4 aaa\nL 4 aaa\nU 6 aaa\nR 3 aaa\nU 5 aaa\nL 3 aaa\nU 4 aaa";
-        assert_eq!(solve(input), 160);
+        assert_eq!(solve(input), Ok(160));
     }
+    #[test]
+    fn test_not_closed() {
+        let input = "R 2 aaa\nD 2 aaa\nL 1 aaa";
+        assert!(matches!(solve(input), Err(PlanError::Invalid { .. })));
+    }
+
     #[test]
     fn test_custom_case_4() {
         let input = "R 2 aaa\nU 2 aaa\nR 3 aaa\nD 3 aaa\nL 2 aaa\nD 2 aaa\nL 3 aaa\nU 3 aaa\n";
-        assert_eq!(solve(input), 28);
+        assert_eq!(solve(input), Ok(28));
     }
 }
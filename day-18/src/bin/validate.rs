@@ -0,0 +1,62 @@
+use day_18::{parse_plan, validate, Interpretation, PlanError};
+
+/// Whether the plan digs a simple loop and which way round, or everything wrong with it.
+fn report(file: &str, interpretation: Interpretation) -> Result<String, PlanError> {
+    let steps = parse_plan(file, interpretation)?;
+    Ok(match validate(&steps) {
+        Ok(orientation) => format!(
+            "{} steps dig a simple loop, going round {}\n",
+            steps.len(),
+            orientation
+        ),
+        Err(issues) => issues.iter().map(|issue| format!("{}\n", issue)).collect(),
+    })
+}
+
+fn main() {
+    let args = std::env::args().collect::<Vec<_>>();
+    let input = match args.iter().position(|a| a == "--plan") {
+        Some(i) => match args.get(i + 1) {
+            Some(path) => std::fs::read_to_string(path).unwrap(),
+            None => {
+                eprintln!("Usage: validate [--plan <file>] [--decoded]");
+                std::process::exit(1);
+            }
+        },
+        None => include_str!("input.txt").to_string(),
+    };
+    let interpretation = match args.iter().any(|a| a == "--decoded") {
+        true => Interpretation::Decoded,
+        false => Interpretation::Plain,
+    };
+    match report(&input, interpretation) {
+        Ok(report) => print!("{}", report),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let square = "R 2 (#000020)\nD 2 (#000021)\nL 2 (#000022)\nU 2 (#000023)";
+        assert_eq!(
+            report(square, Interpretation::Plain).unwrap(),
+            "4 steps dig a simple loop, going round clockwise\n"
+        );
+        let hand_edited = "R 2 (#000020)\nD 2 (#000021)\nL 1 (#000012)\nU 3 (#000033)";
+        assert_eq!(
+            report(hand_edited, Interpretation::Plain).unwrap(),
+            "Ends (1, -1) away from the start\nSteps 0 and 3 meet at (1, 0)\n"
+        );
+        assert_eq!(
+            report("", Interpretation::Plain).unwrap(),
+            "Only 0 steps move, a loop needs at least 4\n"
+        );
+    }
+}
@@ -0,0 +1,661 @@
+use std::{cmp::Ordering, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// `U`, `D`, `L` or `R` as written in the plan.
+    pub fn from_letter(s: &str) -> Option<Direction> {
+        match s {
+            "U" => Some(Direction::Up),
+            "D" => Some(Direction::Down),
+            "L" => Some(Direction::Left),
+            "R" => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// The last hex digit of a colour: 0 means R, 1 D, 2 L and 3 U.
+    pub fn from_digit(digit: u32) -> Option<Direction> {
+        match digit {
+            0 => Some(Direction::Right),
+            1 => Some(Direction::Down),
+            2 => Some(Direction::Left),
+            3 => Some(Direction::Up),
+            _ => None,
+        }
+    }
+
+    /// With y growing downwards, like the SVG and the preview.
+    pub fn to_vec(self) -> (i64, i64) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+}
+
+/// A `#rrggbb` colour as one number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Colour(pub u32);
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:06x}", self.0)
+    }
+}
+
+/// How to read a line of the plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    /// The direction and distance as written, for part 1.
+    Plain,
+    /// The distance from the first five hex digits of the colour and the direction from the
+    /// last, for part 2.
+    Decoded,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanError {
+    InvalidStep {
+        line: usize,
+        text: String,
+    },
+    InvalidColour {
+        line: usize,
+        colour: Colour,
+    },
+    /// The plan is not a simple closed loop, so its area means nothing.
+    Invalid {
+        issues: Vec<PlanIssue>,
+    },
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::InvalidStep { line, text } => {
+                write!(f, "Invalid step on line {}: {}", line, text)
+            }
+            PlanError::InvalidColour { line, colour } => write!(
+                f,
+                "Colour {} on line {} does not encode a direction",
+                colour, line
+            ),
+            PlanError::Invalid { issues } => {
+                write!(f, "The plan is not a simple loop:")?;
+                for issue in issues {
+                    write!(f, "\n  {}", issue)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// One line of the dig plan, e.g. `R 6 (#70c710)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigStep {
+    pub dir: Direction,
+    pub dist: i64,
+    pub colour: Colour,
+}
+
+impl DigStep {
+    /// The step as written, `line` counting from 1 for the error.
+    pub fn parse(text: &str, line: usize) -> Result<DigStep, PlanError> {
+        let invalid = || PlanError::InvalidStep {
+            line,
+            text: text.to_string(),
+        };
+        let parts = text.split_whitespace().collect::<Vec<_>>();
+        let [dir, dist, colour] = parts[..] else {
+            return Err(invalid());
+        };
+        let hex = colour
+            .strip_prefix("(#")
+            .and_then(|c| c.strip_suffix(')'))
+            .filter(|hex| hex.len() == 6)
+            .ok_or_else(invalid)?;
+        Ok(DigStep {
+            dir: Direction::from_letter(dir).ok_or_else(invalid)?,
+            dist: dist.parse::<u32>().map_err(|_| invalid())? as i64,
+            colour: Colour(u32::from_str_radix(hex, 16).map_err(|_| invalid())?),
+        })
+    }
+
+    /// The step the colour encodes, keeping the colour.
+    pub fn decoded(&self, line: usize) -> Result<DigStep, PlanError> {
+        let dir = Direction::from_digit(self.colour.0 & 0xf).ok_or(PlanError::InvalidColour {
+            line,
+            colour: self.colour,
+        })?;
+        Ok(DigStep {
+            dir,
+            dist: (self.colour.0 >> 4) as i64,
+            colour: self.colour,
+        })
+    }
+}
+
+pub fn parse_plan(file: &str, interpretation: Interpretation) -> Result<Vec<DigStep>, PlanError> {
+    file.lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .map(|(i, text)| {
+            let step = DigStep::parse(text, i + 1)?;
+            match interpretation {
+                Interpretation::Plain => Ok(step),
+                Interpretation::Decoded => step.decoded(i + 1),
+            }
+        })
+        .collect()
+}
+
+/// The corners of the trench, starting and ending where the digger starts at (0, 0).
+pub fn corners(steps: &[DigStep]) -> Vec<(i64, i64)> {
+    let mut pos = (0, 0);
+    let mut corners = vec![pos];
+    for step in steps {
+        let (dx, dy) = step.dir.to_vec();
+        pos = (pos.0 + dx * step.dist, pos.1 + dy * step.dist);
+        corners.push(pos);
+    }
+    corners
+}
+
+/// Cubic meters the lagoon holds: the shoelace area of the trench's centre line, plus the half
+/// of every trench block outside of it, plus the four outer corners' extra quarter.
+pub fn lagoon_volume(steps: &[DigStep]) -> i64 {
+    let twice_area = corners(steps)
+        .windows(2)
+        .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+        .sum::<i64>();
+    let trench = steps.iter().map(|s| s.dist).sum::<i64>();
+    twice_area.abs() / 2 + trench / 2 + 1
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Orientation::Clockwise => write!(f, "clockwise"),
+            Orientation::CounterClockwise => write!(f, "counter-clockwise"),
+        }
+    }
+}
+
+/// Something that keeps the trench from being a simple loop. Steps are counted from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanIssue {
+    /// Fewer than the four moving steps any loop needs.
+    TooShort { steps: usize },
+    /// The digger ends up `offset` away from where it started.
+    NotClosed { offset: (i64, i64) },
+    /// A step that does not move.
+    Empty { step: usize },
+    /// Two steps meet in a single block other than the corner they share.
+    Intersection {
+        first: usize,
+        second: usize,
+        at: (i64, i64),
+    },
+    /// Two steps dig along the same stretch of trench.
+    Overlap {
+        first: usize,
+        second: usize,
+        from: (i64, i64),
+        to: (i64, i64),
+    },
+    /// The trench closes without enclosing anything, so it goes neither way round.
+    NoArea,
+}
+
+impl fmt::Display for PlanIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanIssue::TooShort { steps } => {
+                write!(f, "Only {} steps move, a loop needs at least 4", steps)
+            }
+            PlanIssue::NotClosed { offset } => {
+                write!(f, "Ends {:?} away from the start", offset)
+            }
+            PlanIssue::Empty { step } => write!(f, "Step {} does not move", step),
+            PlanIssue::Intersection { first, second, at } => {
+                write!(f, "Steps {} and {} meet at {:?}", first, second, at)
+            }
+            PlanIssue::Overlap {
+                first,
+                second,
+                from,
+                to,
+            } => write!(
+                f,
+                "Steps {} and {} overlap from {:?} to {:?}",
+                first, second, from, to
+            ),
+            PlanIssue::NoArea => write!(f, "Encloses no area"),
+        }
+    }
+}
+
+/// The blocks two straight steps have in common, as the first and last of them.
+fn shared(a: &[(i64, i64)], b: &[(i64, i64)]) -> Option<((i64, i64), (i64, i64))> {
+    let low = |s: &[(i64, i64)]| (s[0].0.min(s[1].0), s[0].1.min(s[1].1));
+    let high = |s: &[(i64, i64)]| (s[0].0.max(s[1].0), s[0].1.max(s[1].1));
+    let from = (low(a).0.max(low(b).0), low(a).1.max(low(b).1));
+    let to = (high(a).0.min(high(b).0), high(a).1.min(high(b).1));
+    (from.0 <= to.0 && from.1 <= to.1).then_some((from, to))
+}
+
+/// Checks that the plan digs a simple closed loop, and which way round it goes. Otherwise
+/// every issue found, in step order.
+pub fn validate(steps: &[DigStep]) -> Result<Orientation, Vec<PlanIssue>> {
+    let corners = corners(steps);
+    let end = *corners.last().unwrap();
+    let closed = end == (0, 0);
+    let mut issues = vec![];
+    let moving = steps.iter().filter(|s| s.dist > 0).count();
+    if moving < 4 {
+        issues.push(PlanIssue::TooShort { steps: moving });
+    }
+    if !closed {
+        issues.push(PlanIssue::NotClosed { offset: end });
+    }
+
+    let edges = corners.windows(2).collect::<Vec<_>>();
+    for (i, step) in steps.iter().enumerate() {
+        if step.dist == 0 {
+            issues.push(PlanIssue::Empty { step: i });
+        }
+    }
+    for i in 0..edges.len() {
+        for j in i + 1..edges.len() {
+            let Some((from, to)) = shared(edges[i], edges[j]) else {
+                continue;
+            };
+            // Neighbouring steps share their corner, the last and first ones too on a loop.
+            // Empty steps in between are already reported, so they do not count.
+            let empty = |between: &[DigStep]| between.iter().all(|s| s.dist == 0);
+            let neighbours =
+                empty(&steps[i + 1..j]) || (closed && empty(&steps[j + 1..]) && empty(&steps[..i]));
+            if from != to {
+                issues.push(PlanIssue::Overlap {
+                    first: i,
+                    second: j,
+                    from,
+                    to,
+                });
+            } else if !neighbours && steps[i].dist > 0 && steps[j].dist > 0 {
+                issues.push(PlanIssue::Intersection {
+                    first: i,
+                    second: j,
+                    at: from,
+                });
+            }
+        }
+    }
+    if !issues.is_empty() {
+        return Err(issues);
+    }
+
+    // With y growing downwards a positive shoelace sum goes round clockwise
+    let twice_area = corners
+        .windows(2)
+        .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+        .sum::<i64>();
+    match twice_area.cmp(&0) {
+        Ordering::Greater => Ok(Orientation::Clockwise),
+        Ordering::Less => Ok(Orientation::CounterClockwise),
+        Ordering::Equal => Err(vec![PlanIssue::NoArea]),
+    }
+}
+
+/// The lagoon's volume, once the plan is known to be a simple loop.
+pub fn checked_volume(steps: &[DigStep]) -> Result<i64, PlanError> {
+    validate(steps).map_err(|issues| PlanError::Invalid { issues })?;
+    Ok(lagoon_volume(steps))
+}
+
+/// (min x, min y, max x, max y) over the corners.
+fn bounds(corners: &[(i64, i64)]) -> (i64, i64, i64, i64) {
+    corners.iter().fold(
+        (i64::MAX, i64::MAX, i64::MIN, i64::MIN),
+        |(x0, y0, x1, y1), &(x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+    )
+}
+
+/// The trench as an SVG: the lagoon filled, and every step a polyline in its own colour.
+pub fn svg(steps: &[DigStep]) -> String {
+    let corners = corners(steps);
+    let (x0, y0, x1, y1) = bounds(&corners);
+    let stroke = ((x1 - x0).max(y1 - y0) / 200).max(1);
+    let points = |points: &[(i64, i64)]| {
+        points
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        x0 - stroke,
+        y0 - stroke,
+        x1 - x0 + 2 * stroke,
+        y1 - y0 + 2 * stroke
+    );
+    out += &format!(
+        "  <polygon points=\"{}\" fill=\"#d8d8d8\" stroke=\"none\"/>\n",
+        points(&corners)
+    );
+    for (step, edge) in steps.iter().zip(corners.windows(2)) {
+        out += &format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"square\"/>\n",
+            points(edge),
+            step.colour,
+            stroke
+        );
+    }
+    out += "</svg>\n";
+    out
+}
+
+/// Whether a point lies inside the trench's centre line, by counting the vertical edges to its
+/// left.
+fn inside(corners: &[(i64, i64)], x: f64, y: f64) -> bool {
+    corners
+        .windows(2)
+        .filter(|w| w[0].0 == w[1].0 && (w[0].0 as f64) < x)
+        .filter(|w| {
+            let (low, high) = (w[0].1.min(w[1].1), w[0].1.max(w[1].1));
+            (low as f64) < y && y < high as f64
+        })
+        .count()
+        % 2
+        == 1
+}
+
+/// The trench shrunk to fit in `width` by `height` characters, each covering a square of
+/// blocks: `#` where it holds trench, `:` inside the lagoon and `.` outside.
+pub fn preview(steps: &[DigStep], width: usize, height: usize) -> String {
+    let corners = corners(steps);
+    let (x0, y0, x1, y1) = bounds(&corners);
+    let (w, h) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+    let scale = w
+        .div_ceil(width.max(1))
+        .max(h.div_ceil(height.max(1)))
+        .max(1);
+    let (cols, rows) = (w.div_ceil(scale), h.div_ceil(scale));
+    let scale = scale as i64;
+
+    let mut cells = vec![vec![None; cols]; rows];
+    for edge in corners.windows(2) {
+        let (ax, ay) = ((edge[0].0 - x0) / scale, (edge[0].1 - y0) / scale);
+        let (bx, by) = ((edge[1].0 - x0) / scale, (edge[1].1 - y0) / scale);
+        for row in &mut cells[ay.min(by) as usize..=ay.max(by) as usize] {
+            for cell in &mut row[ax.min(bx) as usize..=ax.max(bx) as usize] {
+                *cell = Some('#');
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (row, line) in cells.into_iter().enumerate() {
+        for (col, cell) in line.into_iter().enumerate() {
+            out.push(cell.unwrap_or_else(|| {
+                // The middle block, nudged off the grid lines it could share with a corner
+                let x = (x0 + col as i64 * scale + scale / 2) as f64 + 0.25;
+                let y = (y0 + row as i64 * scale + scale / 2) as f64 + 0.25;
+                match inside(&corners, x, y) {
+                    true => ':',
+                    false => '.',
+                }
+            }));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)";
+
+    /// A plan without caring for the colours.
+    fn plain(moves: &str) -> Vec<DigStep> {
+        moves
+            .split(',')
+            .map(|m| DigStep::parse(&format!("{} (#000000)", m.trim()), 0).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let plain = parse_plan(INPUT, Interpretation::Plain).unwrap();
+        assert_eq!(
+            plain[0],
+            DigStep {
+                dir: Direction::Right,
+                dist: 6,
+                colour: Colour(0x70c710)
+            }
+        );
+        assert_eq!(plain[0].colour.to_string(), "#70c710");
+
+        let decoded = parse_plan(INPUT, Interpretation::Decoded).unwrap();
+        assert_eq!(
+            decoded[0],
+            DigStep {
+                dir: Direction::Right,
+                dist: 461937,
+                colour: Colour(0x70c710)
+            }
+        );
+        assert_eq!((decoded[13].dir, decoded[13].dist), (Direction::Up, 500254));
+
+        assert_eq!(
+            parse_plan("R 6 (#70c710)\nX 1 (#000000)", Interpretation::Plain),
+            Err(PlanError::InvalidStep {
+                line: 2,
+                text: "X 1 (#000000)".to_string()
+            })
+        );
+        assert!(parse_plan("R 6 aaa", Interpretation::Plain).is_err());
+        assert_eq!(
+            parse_plan(
+                "R 2 (#000000)\nD 2 (#000000)\nR -2 (#000000)",
+                Interpretation::Plain
+            ),
+            Err(PlanError::InvalidStep {
+                line: 3,
+                text: "R -2 (#000000)".to_string()
+            })
+        );
+        assert_eq!(
+            parse_plan("R 6 (#000004)", Interpretation::Decoded),
+            Err(PlanError::InvalidColour {
+                line: 1,
+                colour: Colour(4)
+            })
+        );
+    }
+
+    #[test]
+    fn test_lagoon_volume() {
+        assert_eq!(
+            lagoon_volume(&parse_plan(INPUT, Interpretation::Plain).unwrap()),
+            62
+        );
+        assert_eq!(
+            lagoon_volume(&parse_plan(INPUT, Interpretation::Decoded).unwrap()),
+            952408144115
+        );
+        // Notches in and out of every side
+        assert_eq!(
+            lagoon_volume(&plain("R 2, D 2, R 2, U 2, R 2, D 3, L 6, U 3")),
+            26
+        );
+        assert_eq!(
+            lagoon_volume(&plain("R 2, U 2, R 2, D 2, R 2, U 3, L 6, D 3")),
+            26
+        );
+        assert_eq!(
+            lagoon_volume(&plain(
+                "R 9, D 8, R 3, D 7, L 4, U 4, L 4, D 4, L 4, U 6, R 3, U 5, L 3, U 4"
+            )),
+            160
+        );
+        assert_eq!(
+            lagoon_volume(&plain("R 2, U 2, R 3, D 3, L 2, D 2, L 3, U 3")),
+            28
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let steps = parse_plan(INPUT, Interpretation::Plain).unwrap();
+        assert_eq!(validate(&steps), Ok(Orientation::Clockwise));
+        let decoded = parse_plan(INPUT, Interpretation::Decoded).unwrap();
+        assert_eq!(validate(&decoded), Ok(Orientation::Clockwise));
+        assert_eq!(
+            validate(&plain("U 2, L 2, D 2, R 2")),
+            Ok(Orientation::CounterClockwise)
+        );
+
+        assert_eq!(
+            validate(&plain("R 2, D 2, L 2, U 1")),
+            Err(vec![PlanIssue::NotClosed { offset: (0, 1) }])
+        );
+        assert_eq!(validate(&[]), Err(vec![PlanIssue::TooShort { steps: 0 }]));
+        assert_eq!(
+            validate(&plain("R 2, L 2")),
+            Err(vec![
+                PlanIssue::TooShort { steps: 2 },
+                PlanIssue::Overlap {
+                    first: 0,
+                    second: 1,
+                    from: (0, 0),
+                    to: (2, 0)
+                },
+            ])
+        );
+        // A figure of eight, crossing itself in the middle
+        assert_eq!(
+            validate(&plain("R 2, D 4, R 2, U 2, L 4, U 2")),
+            Err(vec![PlanIssue::Intersection {
+                first: 1,
+                second: 4,
+                at: (2, 2)
+            }])
+        );
+        // Doubling back over the last block, so the next step starts on the first one
+        assert_eq!(
+            validate(&plain("R 3, L 1, D 2, L 2, U 2")),
+            Err(vec![
+                PlanIssue::Overlap {
+                    first: 0,
+                    second: 1,
+                    from: (2, 0),
+                    to: (3, 0)
+                },
+                PlanIssue::Intersection {
+                    first: 0,
+                    second: 2,
+                    at: (2, 0)
+                },
+            ])
+        );
+        // Coming home along the first step
+        assert_eq!(
+            validate(&plain("R 4, D 2, L 2, U 2, L 2")),
+            Err(vec![
+                PlanIssue::Intersection {
+                    first: 0,
+                    second: 3,
+                    at: (2, 0)
+                },
+                PlanIssue::Overlap {
+                    first: 0,
+                    second: 4,
+                    from: (0, 0),
+                    to: (2, 0)
+                },
+            ])
+        );
+        assert_eq!(
+            checked_volume(&plain("R 2, D 0, D 2, L 2, U 2")),
+            Err(PlanError::Invalid {
+                issues: vec![PlanIssue::Empty { step: 1 }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg(&parse_plan(
+            "R 2 (#ff0000)\nD 1 (#00ff00)\nL 2 (#0000ff)\nU 1 (#123456)",
+            Interpretation::Plain,
+        )
+        .unwrap());
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -1 4 3\">\n")
+        );
+        assert!(svg.contains("<polygon points=\"0,0 2,0 2,1 0,1 0,0\" fill=\"#d8d8d8\""));
+        assert!(svg.contains("<polyline points=\"2,0 2,1\" fill=\"none\" stroke=\"#00ff00\""));
+        assert_eq!(svg.matches("<polyline").count(), 4);
+    }
+
+    #[test]
+    fn test_preview() {
+        // Full size it is the puzzle's picture, with the lagoon's inside marked
+        let steps = parse_plan(INPUT, Interpretation::Plain).unwrap();
+        assert_eq!(
+            preview(&steps, 80, 40),
+            "#######
+#:::::#
+###:::#
+..#:::#
+..#:::#
+###:###
+#:::#..
+##::###
+.#::::#
+.######
+"
+        );
+        // Halved, every character covers two by two blocks
+        assert_eq!(preview(&steps, 4, 5), "####\n##:#\n####\n#:##\n####\n");
+        let decoded = parse_plan(INPUT, Interpretation::Decoded).unwrap();
+        let small = preview(&decoded, 20, 10);
+        assert!(small.lines().all(|l| l.len() <= 20) && small.lines().count() <= 10);
+        assert!(small.contains(':'));
+    }
+}
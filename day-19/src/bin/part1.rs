@@ -1,151 +1,14 @@
-use std::{collections::HashMap, time::Instant};
-
-enum Rule {
-    ComparisonRule(ComparisonRule),
-    DefaultRule(Destination),
-}
-
-impl Rule {
-    fn next(&self, part: &Part) -> Option<&Destination> {
-        match self {
-            Self::ComparisonRule(comparison) => {
-                let value = match comparison.field.as_str() {
-                    "x" => part.x,
-                    "m" => part.m,
-                    "a" => part.a,
-                    "s" => part.s,
-                    _ => panic!("Invalid field: {}", comparison.field),
-                };
-                if value > comparison.min && value < comparison.max {
-                    Some(&comparison.next)
-                } else {
-                    None
-                }
-            }
-            Self::DefaultRule(dest) => Some(dest),
-        }
-    }
-}
-
-struct ComparisonRule {
-    field: String,
-    min: i64,
-    max: i64,
-    next: Destination,
-}
-
-impl From<&str> for Rule {
-    fn from(s: &str) -> Self {
-        if let Some((comparison, next)) = s.split_once(':') {
-            if let Some((field, value)) = comparison.split_once('<') {
-                Self::ComparisonRule(ComparisonRule {
-                    field: field.to_string(),
-                    min: i64::MIN,
-                    max: value.parse().unwrap(),
-                    next: next.into(),
-                })
-            } else if let Some((field, value)) = comparison.split_once('>') {
-                Self::ComparisonRule(ComparisonRule {
-                    field: field.to_string(),
-                    min: value.parse().unwrap(),
-                    max: i64::MAX,
-                    next: next.into(),
-                })
-            } else {
-                panic!("Invalid comparison rule: {}", s);
-            }
-        } else {
-            Self::DefaultRule(s.into())
-        }
-    }
-}
-
-enum Destination {
-    A,
-    R,
-    Next(String),
-}
-
-impl From<&str> for Destination {
-    fn from(s: &str) -> Self {
-        match s {
-            "A" => Self::A,
-            "R" => Self::R,
-            _ => Self::Next(s.to_string()),
-        }
-    }
-}
-
-struct Part {
-    x: i64,
-    m: i64,
-    a: i64,
-    s: i64,
-}
-
-impl Part {
-    fn sum(&self) -> i64 {
-        self.x + self.m + self.a + self.s
-    }
-}
+use day_19::{accepts, parse_parts, parse_workflows};
+use std::time::Instant;
 
 fn solve(file: &str) -> i64 {
-    let mut workflow: HashMap<String, Vec<Rule>> = HashMap::new();
-    let mut parts: Vec<Part> = Vec::new();
-
-    //Parse the input
-    let sections: Vec<_> = file.split("\n\n").collect();
-    sections[0].lines().for_each(|l| {
-        let (name, rules) = l.split_once('{').unwrap();
-        let rules = rules.trim_end_matches('}');
-        let rules: Vec<_> = rules.split(',').map(Rule::from).collect();
-        workflow.insert(name.to_string(), rules);
-    });
-    sections[1].lines().for_each(|l| {
-        let l = l.trim_start_matches('{');
-        let l = l.trim_end_matches("}");
-        let attr = l.split(',');
-        let mut part = Part {
-            x: 0,
-            m: 0,
-            a: 0,
-            s: 0,
-        };
-        attr.for_each(|p| {
-            let (field, value) = p.split_once('=').unwrap();
-            match field {
-                "x" => part.x = value.parse().unwrap(),
-                "m" => part.m = value.parse().unwrap(),
-                "a" => part.a = value.parse().unwrap(),
-                "s" => part.s = value.parse().unwrap(),
-                _ => panic!("Invalid field: {}", field),
-            }
-        });
-        parts.push(part);
-    });
-
-    let mut acc = 0;
-    for part in parts {
-        let mut current = "in";
-
-        'workflowloop: loop {
-            'ruleloop: for rule in workflow.get(current).unwrap() {
-                match rule.next(&part) {
-                    Some(Destination::A) => {
-                        acc += part.sum();
-                        break 'workflowloop;
-                    }
-                    Some(Destination::R) => break 'workflowloop,
-                    Some(Destination::Next(next)) => {
-                        current = next;
-                        break 'ruleloop;
-                    }
-                    None => (),
-                }
-            }
-        }
-    }
-    acc
+    let (workflows, parts) = file.split_once("\n\n").unwrap();
+    let workflows = parse_workflows(workflows);
+    parse_parts(parts)
+        .iter()
+        .filter(|part| accepts(&workflows, part))
+        .map(|part| part.sum())
+        .sum()
 }
 
 fn main() {
@@ -0,0 +1,49 @@
+use day_19::{count_accepted, parse_workflows, PartRanges};
+use std::time::Instant;
+
+/// Every combination of ratings from 1 to 4000 the workflows accept.
+fn solve(file: &str) -> i64 {
+    let (workflows, _) = file.split_once("\n\n").unwrap();
+    count_accepted(
+        &parse_workflows(workflows),
+        "in",
+        PartRanges::uniform(1, 4000),
+    )
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+    println!("Starting solution");
+    let t0 = Instant::now();
+    let result = solve(input);
+    let duration = t0.elapsed();
+    println!("Result: {}", result);
+    println!("Time: {:?}", duration);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_part() {
+        let input = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}";
+        assert_eq!(solve(input), 167409079868000);
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+pub enum Rule {
+    ComparisonRule(ComparisonRule),
+    DefaultRule(Destination),
+}
+
+impl Rule {
+    pub fn next(&self, part: &Part) -> Option<&Destination> {
+        match self {
+            Self::ComparisonRule(comparison) => {
+                let value = part.get(&comparison.field);
+                if value > comparison.min && value < comparison.max {
+                    Some(&comparison.next)
+                } else {
+                    None
+                }
+            }
+            Self::DefaultRule(dest) => Some(dest),
+        }
+    }
+}
+
+/// Sends parts with `min < field < max` on to `next`. A `<` rule leaves `min` at `i64::MIN`
+/// and a `>` rule leaves `max` at `i64::MAX`.
+pub struct ComparisonRule {
+    pub field: String,
+    pub min: i64,
+    pub max: i64,
+    pub next: Destination,
+}
+
+impl ComparisonRule {
+    /// The parts of `ranges` the rule sends on, and those it passes to the next rule.
+    pub fn split(&self, ranges: &PartRanges) -> (Option<PartRanges>, Option<PartRanges>) {
+        let i = field_index(&self.field);
+        let (low, high) = ranges.0[i];
+        let with = |bounds: (i64, i64)| {
+            let mut split = *ranges;
+            split.0[i] = bounds;
+            (bounds.0 <= bounds.1).then_some(split)
+        };
+        let matched = with((low.max(self.min + 1), high.min(self.max - 1)));
+        // Only one side is bounded, so what is left over is a single range
+        let rest = match self.min {
+            i64::MIN => with((low.max(self.max), high)),
+            _ => with((low, high.min(self.min))),
+        };
+        (matched, rest)
+    }
+}
+
+impl From<&str> for Rule {
+    fn from(s: &str) -> Self {
+        if let Some((comparison, next)) = s.split_once(':') {
+            if let Some((field, value)) = comparison.split_once('<') {
+                Self::ComparisonRule(ComparisonRule {
+                    field: field.to_string(),
+                    min: i64::MIN,
+                    max: value.parse().unwrap(),
+                    next: next.into(),
+                })
+            } else if let Some((field, value)) = comparison.split_once('>') {
+                Self::ComparisonRule(ComparisonRule {
+                    field: field.to_string(),
+                    min: value.parse().unwrap(),
+                    max: i64::MAX,
+                    next: next.into(),
+                })
+            } else {
+                panic!("Invalid comparison rule: {}", s);
+            }
+        } else {
+            Self::DefaultRule(s.into())
+        }
+    }
+}
+
+pub enum Destination {
+    A,
+    R,
+    Next(String),
+}
+
+impl From<&str> for Destination {
+    fn from(s: &str) -> Self {
+        match s {
+            "A" => Self::A,
+            "R" => Self::R,
+            _ => Self::Next(s.to_string()),
+        }
+    }
+}
+
+fn field_index(field: &str) -> usize {
+    match field {
+        "x" => 0,
+        "m" => 1,
+        "a" => 2,
+        "s" => 3,
+        _ => panic!("Invalid field: {}", field),
+    }
+}
+
+pub struct Part {
+    pub x: i64,
+    pub m: i64,
+    pub a: i64,
+    pub s: i64,
+}
+
+impl Part {
+    pub fn get(&self, field: &str) -> i64 {
+        [self.x, self.m, self.a, self.s][field_index(field)]
+    }
+
+    pub fn sum(&self) -> i64 {
+        self.x + self.m + self.a + self.s
+    }
+}
+
+/// A hyper-rectangle of parts: an inclusive range for each of `x`, `m`, `a` and `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartRanges(pub [(i64, i64); 4]);
+
+impl PartRanges {
+    /// Every field ranging over `low..=high`.
+    pub fn uniform(low: i64, high: i64) -> Self {
+        PartRanges([(low, high); 4])
+    }
+
+    /// The number of parts inside.
+    pub fn volume(&self) -> i64 {
+        self.0.iter().map(|(low, high)| high - low + 1).product()
+    }
+}
+
+pub type Workflows = HashMap<String, Vec<Rule>>;
+
+pub fn parse_workflows(section: &str) -> Workflows {
+    section
+        .lines()
+        .map(|l| {
+            let (name, rules) = l.split_once('{').unwrap();
+            let rules = rules.trim_end_matches('}');
+            (name.to_string(), rules.split(',').map(Rule::from).collect())
+        })
+        .collect()
+}
+
+pub fn parse_parts(section: &str) -> Vec<Part> {
+    section
+        .lines()
+        .map(|l| {
+            let l = l.trim_start_matches('{').trim_end_matches('}');
+            let mut part = Part {
+                x: 0,
+                m: 0,
+                a: 0,
+                s: 0,
+            };
+            l.split(',').for_each(|p| {
+                let (field, value) = p.split_once('=').unwrap();
+                match field {
+                    "x" => part.x = value.parse().unwrap(),
+                    "m" => part.m = value.parse().unwrap(),
+                    "a" => part.a = value.parse().unwrap(),
+                    "s" => part.s = value.parse().unwrap(),
+                    _ => panic!("Invalid field: {}", field),
+                }
+            });
+            part
+        })
+        .collect()
+}
+
+/// Follows the part through the workflows from `in`.
+pub fn accepts(workflows: &Workflows, part: &Part) -> bool {
+    let mut current = "in";
+    loop {
+        let destination = workflows[current]
+            .iter()
+            .find_map(|rule| rule.next(part))
+            .expect("Workflow without a default rule");
+        match destination {
+            Destination::A => return true,
+            Destination::R => return false,
+            Destination::Next(next) => current = next,
+        }
+    }
+}
+
+/// The number of parts in `ranges` the workflows accept, starting at `workflow`. Every rule
+/// splits the ranges in what it sends on and what it passes to the next rule.
+pub fn count_accepted(workflows: &Workflows, workflow: &str, ranges: PartRanges) -> i64 {
+    let sent = |destination: &Destination, ranges: PartRanges| match destination {
+        Destination::A => ranges.volume(),
+        Destination::R => 0,
+        Destination::Next(next) => count_accepted(workflows, next, ranges),
+    };
+
+    let mut count = 0;
+    let mut left = Some(ranges);
+    for rule in &workflows[workflow] {
+        let Some(ranges) = left else {
+            break;
+        };
+        match rule {
+            Rule::ComparisonRule(comparison) => {
+                let (matched, rest) = comparison.split(&ranges);
+                if let Some(matched) = matched {
+                    count += sent(&comparison.next, matched);
+                }
+                left = rest;
+            }
+            Rule::DefaultRule(destination) => {
+                count += sent(destination, ranges);
+                left = None;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOWS: &str = "px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}";
+
+    /// Every part with fields in `low..=high`, one at a time.
+    fn brute_force(workflows: &Workflows, low: i64, high: i64) -> i64 {
+        let values = || low..=high;
+        let mut count = 0;
+        for x in values() {
+            for m in values() {
+                for a in values() {
+                    for s in values() {
+                        count += accepts(workflows, &Part { x, m, a, s }) as i64;
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_split() {
+        let Rule::ComparisonRule(rule) = Rule::from("m<5:A") else {
+            panic!("Not a comparison");
+        };
+        let ranges = PartRanges::uniform(1, 10);
+        let (matched, rest) = rule.split(&ranges);
+        assert_eq!(matched.unwrap().0[1], (1, 4));
+        assert_eq!(rest.unwrap().0[1], (5, 10));
+        assert_eq!(rule.split(&PartRanges::uniform(5, 10)).0, None);
+
+        let Rule::ComparisonRule(rule) = Rule::from("s>5:R") else {
+            panic!("Not a comparison");
+        };
+        let (matched, rest) = rule.split(&ranges);
+        assert_eq!(matched.unwrap().0[3], (6, 10));
+        assert_eq!(rest.unwrap().0[3], (1, 5));
+        assert_eq!(rule.split(&PartRanges::uniform(6, 10)).1, None);
+    }
+
+    #[test]
+    fn test_count_accepted() {
+        let workflows = parse_workflows(WORKFLOWS);
+        assert_eq!(
+            count_accepted(&workflows, "in", PartRanges::uniform(1, 4000)),
+            167409079868000
+        );
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // The example with every threshold divided by 250, so 1..=16 covers all of them
+        let mut shrunk = String::new();
+        let mut number = String::new();
+        for c in WORKFLOWS.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            if !number.is_empty() {
+                shrunk += &(number.parse::<i64>().unwrap() / 250).to_string();
+                number.clear();
+            }
+            shrunk.push(c);
+        }
+        assert!(shrunk.starts_with("px{a<8:qkq,m>8:A,rfg}"));
+
+        for workflows in [parse_workflows(&shrunk), parse_workflows(WORKFLOWS)] {
+            for (low, high) in [(1, 16), (3, 9)] {
+                assert_eq!(
+                    count_accepted(&workflows, "in", PartRanges::uniform(low, high)),
+                    brute_force(&workflows, low, high)
+                );
+            }
+        }
+    }
+}
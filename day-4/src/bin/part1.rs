@@ -1,37 +1,14 @@
+use day_4::parse_cards;
 use std::time::Instant;
 
-fn get_numbrs(row: &str) -> Vec<u32> {
-    // println!("Some numbers: {}", row);
-    let o = &row
-        .split(" ")
-        .filter(|w| !w.is_empty())
-        .map(|n| n.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
-
-    // dbg!(o);
-    o.to_vec()
-}
 fn solve(file: &str) -> usize {
-    file.lines()
-        .map(|l| l.split(":").nth(1).unwrap())
-        // .inspect(|s| println!("NUMBER PART: {}", s))
-        .map(|l| {
-            let number_strings = l.split("|").collect::<Vec<&str>>();
-            assert_eq!(number_strings.len(), 2);
-            let winning_numbers = get_numbrs(number_strings[0]);
-            let holding_numbers = get_numbrs(number_strings[1]);
-
-            let win_count: usize = holding_numbers
-                .iter()
-                .filter(|n| winning_numbers.contains(n))
-                .count();
-
-            // println!("Win_count: {}", win_count);
-
+    parse_cards(file)
+        .iter()
+        .map(|card| {
             // 2 ^ (win_count as i32 - 1) as u32
             let mut res = 0;
-            if win_count > 0 {
-                res = 2_usize.pow(win_count as u32 - 1)
+            if card.n_wins > 0 {
+                res = 2_usize.pow(card.n_wins as u32 - 1)
             }
 
             res
@@ -1,60 +1,38 @@
+use day_4::{parse_cards, play, CardCopies, Overflow, OverflowError};
 use std::time::Instant;
 
-struct ScratchCard {
-    n_wins: usize,
-    n_copies: usize,
+fn play_cards(file: &str, overflow: Overflow) -> Result<Vec<CardCopies>, OverflowError> {
+    play(&parse_cards(file), overflow)
 }
 
-fn get_numbrs(row: &str) -> Vec<u32> {
-    // println!("Some numbers: {}", row);
-    let o = &row
-        .split(" ")
-        .filter(|w| !w.is_empty())
-        .map(|n| n.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
-
-    // dbg!(o);
-    o.to_vec()
-}
-fn solve(file: &str) -> usize {
-    let mut cards = file
-        .lines()
-        .map(|l| l.split(":").nth(1).unwrap())
-        // .inspect(|s| println!("NUMBER PART: {}", s))
-        .map(|l| {
-            let number_strings = l.split("|").collect::<Vec<&str>>();
-            assert_eq!(number_strings.len(), 2);
-            let winning_numbers = get_numbrs(number_strings[0]);
-            let holding_numbers = get_numbrs(number_strings[1]);
-
-            let win_count: usize = holding_numbers
-                .iter()
-                .filter(|n| winning_numbers.contains(n))
-                .count();
-
-            ScratchCard {
-                n_wins: win_count,
-                n_copies: 1,
-            }
-        })
-        .collect::<Vec<ScratchCard>>();
-
-    for i in 0..cards.len() {
-        for w in 1..=cards[i].n_wins {
-            cards[i + w].n_copies += cards[i].n_copies;
-        }
-    }
-
+fn total_copies(cards: &[CardCopies]) -> usize {
     cards.iter().map(|c| c.n_copies).sum()
 }
 
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let trace = args.iter().any(|a| a == "--trace");
+    let overflow = match args.iter().any(|a| a == "--strict") {
+        true => Overflow::Error,
+        false => Overflow::Clamp,
+    };
+
     let input = include_str!("input.txt");
-    println!("Starting solution for part 1");
+    println!("Starting solution for part 2");
     let t0 = Instant::now();
-    let result = solve(input);
+    let cards = match play_cards(input, overflow) {
+        Ok(cards) => cards,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let result = total_copies(&cards);
     let t1 = Instant::now();
 
+    if trace {
+        cards.iter().for_each(|c| println!("{}", c));
+    }
     println!("Result: {}", result);
     println!("Time: {:?}", (t1 - t0).as_micros());
 }
@@ -71,6 +49,38 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(solve(input), 30);
+        let cards = play_cards(input, Overflow::Clamp).unwrap();
+        assert_eq!(total_copies(&cards), 30);
+    }
+
+    #[test]
+    fn test_trace() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83";
+        let cards = play_cards(input, Overflow::Clamp).unwrap();
+        assert_eq!(cards[3].n_copies, 8);
+        assert_eq!(cards[3].sources, vec![(1, 1), (2, 2), (3, 4)]);
+        assert_eq!(
+            cards[3].to_string(),
+            "Card 4: 1 wins, 8 copies (1 original, 1 from card 1, 2 from card 2, 4 from card 3)"
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19";
+        let cards = play_cards(input, Overflow::Clamp).unwrap();
+        assert_eq!(total_copies(&cards), 3);
+        assert_eq!(
+            play_cards(input, Overflow::Error),
+            Err(OverflowError {
+                card: 1,
+                n_wins: 4,
+                n_cards: 1
+            })
+        );
     }
 }
@@ -0,0 +1,178 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchCard {
+    pub id: usize,
+    pub n_wins: usize,
+}
+
+/// Largest number kept in the bitset, so a stray huge number can't blow up its size.
+const BITSET_MAX: u32 = 1 << 16;
+
+/// The winning numbers, so every held number is checked without scanning the winning list.
+/// Small numbers, as on real cards, go in a bitset for O(1) lookups; with anything above
+/// `BITSET_MAX` they are kept sorted and binary searched instead.
+enum NumberSet {
+    Bits(Vec<u64>),
+    Sorted(Vec<u32>),
+}
+
+impl NumberSet {
+    fn new(numbers: &[u32]) -> Self {
+        let max = numbers.iter().copied().max().unwrap_or(0);
+        if max > BITSET_MAX {
+            let mut sorted = numbers.to_vec();
+            sorted.sort_unstable();
+            return NumberSet::Sorted(sorted);
+        }
+        let mut words = vec![0_u64; max as usize / 64 + 1];
+        for &n in numbers {
+            words[n as usize / 64] |= 1 << (n % 64);
+        }
+        NumberSet::Bits(words)
+    }
+
+    fn contains(&self, n: u32) -> bool {
+        match self {
+            NumberSet::Bits(words) => words
+                .get(n as usize / 64)
+                .is_some_and(|w| w & (1 << (n % 64)) != 0),
+            NumberSet::Sorted(sorted) => sorted.binary_search(&n).is_ok(),
+        }
+    }
+}
+
+fn get_numbrs(row: &str) -> Vec<u32> {
+    row.split_whitespace()
+        .map(|n| n.parse::<u32>().unwrap())
+        .collect()
+}
+
+pub fn count_matches(winning_numbers: &[u32], holding_numbers: &[u32]) -> usize {
+    let winning = NumberSet::new(winning_numbers);
+    holding_numbers
+        .iter()
+        .filter(|&&n| winning.contains(n))
+        .count()
+}
+
+impl From<&str> for ScratchCard {
+    fn from(line: &str) -> Self {
+        let (name, numbers) = line.split_once(':').unwrap();
+        let id = name.trim_start_matches("Card").trim().parse().unwrap();
+
+        let number_strings = numbers.split('|').collect::<Vec<&str>>();
+        assert_eq!(number_strings.len(), 2);
+        let winning_numbers = get_numbrs(number_strings[0]);
+        let holding_numbers = get_numbrs(number_strings[1]);
+
+        ScratchCard {
+            id,
+            n_wins: count_matches(&winning_numbers, &holding_numbers),
+        }
+    }
+}
+
+pub fn parse_cards(file: &str) -> Vec<ScratchCard> {
+    file.lines().map(ScratchCard::from).collect()
+}
+
+/// What to do when a card wins copies of cards past the end of the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Copies past the last card are dropped.
+    Clamp,
+    /// Winning past the last card is reported as an error.
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverflowError {
+    pub card: usize,
+    pub n_wins: usize,
+    pub n_cards: usize,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Card {} wins {} cards, but only {} cards follow it",
+            self.card, self.n_wins, self.n_cards
+        )
+    }
+}
+
+/// The number of copies of a card after playing, and where they came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardCopies {
+    pub id: usize,
+    pub n_wins: usize,
+    pub n_copies: usize,
+    /// `(card id, copies)` for every earlier card that won copies of this one.
+    pub sources: Vec<(usize, usize)>,
+}
+
+impl fmt::Display for CardCopies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Card {}: {} wins, {} copies (1 original",
+            self.id, self.n_wins, self.n_copies
+        )?;
+        for (card, copies) in self.sources.iter() {
+            write!(f, ", {} from card {}", copies, card)?;
+        }
+        write!(f, ")")
+    }
+}
+
+pub fn play(cards: &[ScratchCard], overflow: Overflow) -> Result<Vec<CardCopies>, OverflowError> {
+    let mut played = cards
+        .iter()
+        .map(|c| CardCopies {
+            id: c.id,
+            n_wins: c.n_wins,
+            n_copies: 1,
+            sources: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    for i in 0..played.len() {
+        let following = played.len() - i - 1;
+        if played[i].n_wins > following && overflow == Overflow::Error {
+            return Err(OverflowError {
+                card: played[i].id,
+                n_wins: played[i].n_wins,
+                n_cards: following,
+            });
+        }
+
+        let (id, n_copies) = (played[i].id, played[i].n_copies);
+        for w in 1..=played[i].n_wins.min(following) {
+            played[i + w].n_copies += n_copies;
+            played[i + w].sources.push((id, n_copies));
+        }
+    }
+
+    Ok(played)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_matches() {
+        assert_eq!(
+            count_matches(&[41, 48, 83, 86, 17], &[83, 86, 6, 31, 17, 9, 48, 53]),
+            4
+        );
+        // Past the bitset cap, without allocating for every number below it
+        assert_eq!(
+            count_matches(&[4000000000, 5], &[5, 4000000000, 3999999999]),
+            2
+        );
+        assert_eq!(count_matches(&[], &[1, 2]), 0);
+    }
+}
@@ -1,143 +1,30 @@
+use day_5::{target_category, Almanac, AlmanacError};
 use std::time::Instant;
 
-#[derive(Debug)]
-enum AlmanacType {
-    Seed,
-    Soil,
-    Fertilizer,
-    Water,
-    Light,
-    Temperature,
-    Humidity,
-    Location,
-}
-
-impl From<&str> for AlmanacType {
-    fn from(s: &str) -> Self {
-        match s {
-            "seed" => AlmanacType::Seed,
-            "soil" => AlmanacType::Soil,
-            "fertilizer" => AlmanacType::Fertilizer,
-            "water" => AlmanacType::Water,
-            "light" => AlmanacType::Light,
-            "temperature" => AlmanacType::Temperature,
-            "humidity" => AlmanacType::Humidity,
-            "location" => AlmanacType::Location,
-            _ => panic!("Unknown almanac type: {}", s),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct ConversionValue {
-    from_val: u64,
-    to_val: u64,
-    range: u64,
-}
-
-impl From<&str> for ConversionValue {
-    fn from(row: &str) -> Self {
-        // println!("Parsing row to conversion: {}", row);
-        let parts = row.split_whitespace();
-        let nums = parts.map(|s| s.parse::<u64>().unwrap()).collect::<Vec<_>>();
-        ConversionValue {
-            from_val: nums[1],
-            to_val: nums[0],
-            range: nums[2],
-        }
-    }
-}
-
-#[derive(Debug)]
-struct ConversionMap {
-    from: AlmanacType,
-    to: AlmanacType,
-    map: Vec<ConversionValue>,
-}
-
-impl ConversionMap {
-    fn convert(&self, value: Option<u64>) -> Option<u64> {
-        if let Some(value) = value {
-            for conversion in self.map.iter() {
-                if (conversion.from_val..=(conversion.from_val + conversion.range)).contains(&value)
-                {
-                    return Some(conversion.to_val + (value - conversion.from_val));
-                }
-            }
-            return Some(value);
-        }
-        None
-    }
-}
-
-fn solve(file: &str) -> u64 {
-    let lines = file.lines().collect::<Vec<_>>();
-
-    let seeds = lines[0]
-        .split(":")
-        .nth(1)
-        .unwrap()
-        .split_whitespace()
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect::<Vec<_>>();
-
-    let mut conversion_maps = Vec::new();
-    let mut reading_map = false;
-    for line in lines[1..].iter() {
-        if line.is_empty() {
-            reading_map = false;
-            continue;
-        }
-
-        if !reading_map {
-            // Starting a new map
-            let parts = line
-                .split_whitespace()
-                .nth(0)
-                .unwrap()
-                .split("-to-")
-                .collect::<Vec<_>>();
-            assert_eq!(parts.len(), 2);
-            let from = AlmanacType::from(parts[0]);
-            let to = AlmanacType::from(parts[1]);
-            conversion_maps.push(ConversionMap {
-                from,
-                to,
-                map: Vec::new(),
-            });
-            reading_map = true;
-            continue;
-        }
-
-        if reading_map {
-            // Reading conversion values
-            conversion_maps
-                .last_mut()
-                .unwrap()
-                .map
-                .push(ConversionValue::from(*line));
-        }
-    }
-
-    // Assume the conversion maps are in the correct order
+fn solve(file: &str, target: &str) -> Result<u64, AlmanacError> {
+    let almanac = Almanac::parse(file)?;
+    let chain = almanac.chain("seed", target)?;
 
-    seeds
+    almanac
+        .seeds
         .iter()
-        .map(|seed| {
-            conversion_maps
-                .iter()
-                .fold(Some(*seed), |acc, map| map.convert(acc))
-        })
-        .flatten()
+        .map(|seed| chain.iter().fold(*seed, |acc, map| map.convert_value(acc)))
         .min()
-        .unwrap_or(11)
+        .ok_or(AlmanacError::NoSeeds)
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let target = target_category();
     println!("Starting solution for part 1");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = match solve(input, &target) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let t1 = Instant::now();
 
     println!("Result: {}", result);
@@ -183,7 +70,61 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!(solve(input), 35);
+        assert_eq!(solve(input, "location"), Ok(35));
+    }
+
+    #[test]
+    fn test_ambiguous_chain() {
+        let input = "seeds: 79 14
+
+seed-to-soil map:
+50 98 2
+
+seed-to-water map:
+0 15 37
+
+soil-to-water map:
+49 53 8
+
+water-to-light map:
+88 18 7";
+        assert_eq!(
+            solve(input, "light"),
+            Err(AlmanacError::AmbiguousChain {
+                category: "seed".to_string(),
+                to: "light".to_string(),
+                options: vec!["soil".to_string(), "water".to_string()]
+            })
+        );
+        // A branch that leads elsewhere is fine
+        assert_eq!(solve(input, "soil"), Ok(14));
+    }
+
+    #[test]
+    fn test_no_seeds() {
+        let input = "seeds:\n\nseed-to-soil map:\n 50 98 2";
+        assert_eq!(solve(input, "soil"), Err(AlmanacError::NoSeeds));
+        assert_eq!(solve("", "soil"), Err(AlmanacError::NoSeeds));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let malformed = |line: usize, text: &str| {
+            Err(AlmanacError::Malformed {
+                line,
+                text: text.to_string(),
+            })
+        };
+        assert_eq!(solve("79 14", "soil"), malformed(1, "79 14"));
+        assert_eq!(solve("seeds: 79 x", "soil"), malformed(1, "seeds: 79 x"));
+        assert_eq!(
+            solve("seeds: 79\n\nseed to soil map:\n50 98 2", "soil"),
+            malformed(3, "seed to soil map:")
+        );
+        assert_eq!(
+            solve("seeds: 79\n\nseed-to-soil map:\n50 98", "soil"),
+            malformed(4, "50 98")
+        );
     }
 
     #[test]
@@ -1,200 +1,86 @@
-use std::{ops::Range, time::Instant};
-
-#[derive(Debug)]
-enum AlmanacType {
-    Seed,
-    Soil,
-    Fertilizer,
-    Water,
-    Light,
-    Temperature,
-    Humidity,
-    Location,
-}
-
-impl From<&str> for AlmanacType {
-    fn from(s: &str) -> Self {
-        match s {
-            "seed" => AlmanacType::Seed,
-            "soil" => AlmanacType::Soil,
-            "fertilizer" => AlmanacType::Fertilizer,
-            "water" => AlmanacType::Water,
-            "light" => AlmanacType::Light,
-            "temperature" => AlmanacType::Temperature,
-            "humidity" => AlmanacType::Humidity,
-            "location" => AlmanacType::Location,
-            _ => panic!("Unknown almanac type: {}", s),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct ConversionValue {
-    from_range: Range<u64>,
-    to_range: Range<u64>,
-}
-
-impl From<&str> for ConversionValue {
-    fn from(row: &str) -> Self {
-        let parts = row.split_whitespace();
-        let nums = parts.map(|s| s.parse::<u64>().unwrap()).collect::<Vec<_>>();
-        ConversionValue {
-            from_range: (nums[1]..(nums[1] + nums[2])),
-            to_range: (nums[0]..(nums[0] + nums[2])),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct ConversionMap {
-    from: AlmanacType,
-    to: AlmanacType,
-    map: Vec<ConversionValue>,
-}
-
-impl ConversionMap {
-    fn convert(&self, starting_range: Vec<Range<u64>>) -> Vec<Range<u64>> {
-        let mut output_ranges = Vec::new();
-        let mut leftover_range = starting_range.clone();
-
-        // println!("Converting {:?} to {:?}", self.from, self.to);
-        for conv in self.map.iter() {
-            let mut new_leftover_range = Vec::new();
-
-            for before in leftover_range.iter() {
-                if before.is_empty() {
-                    continue;
-                }
-
-                // println!("Checking {:?} against {:?}", conv.from_range, before);
-                // Check for no overlap
-                if conv.from_range.start > before.end || conv.from_range.end < before.start {
-                    new_leftover_range.push(before.clone());
-                    continue;
-                }
-
-                // Check complete overlap
-                if before.start <= conv.from_range.start && before.end >= conv.from_range.end {
-                    // println!("Complete overlap conversion range inside");
-                    output_ranges.push(conv.to_range.clone()); // Overlapping range is converted
-
-                    // Non overlapping ranges are added to the leftover range
-                    new_leftover_range.push(before.start..conv.from_range.start);
-                    new_leftover_range.push(conv.from_range.end..before.end);
-                    continue;
-                }
-
-                if before.start >= conv.from_range.start && before.end <= conv.from_range.end {
-                    // println!("Complete overlap conversion range outside");
-                    let difference = before.start - conv.from_range.start;
-                    let new_range = (conv.to_range.start + difference)
-                        ..(conv.to_range.start + difference + before.end - before.start);
-
-                    output_ranges.push(new_range); // Overlapping range is converted
-                    continue;
-                }
-
-                // Check partial overlap
-                // Overlap on beginning of before
-                if before.start > conv.from_range.start && before.start < conv.from_range.end {
-                    let overlap_length = conv.from_range.end - before.start;
-                    let new_range = (conv.to_range.end - overlap_length)..conv.to_range.end;
-                    output_ranges.push(new_range); // Overlapping range is converted
-
-                    new_leftover_range.push(conv.from_range.end..before.end);
-                    continue;
-                }
-                // Overlap near end of before
-                if before.end > conv.from_range.start && before.end < conv.from_range.end {
-                    let overlap_length = before.end - conv.from_range.start;
-                    let new_range = conv.to_range.start..(conv.to_range.start + overlap_length);
-                    output_ranges.push(new_range); // Overlapping range is converted
-
-                    new_leftover_range.push(before.start..conv.from_range.start);
-                    continue;
-                }
-            }
-            leftover_range = new_leftover_range;
-        }
-
-        for leftover in leftover_range.iter().filter(|r| !r.is_empty()) {
-            output_ranges.push(leftover.clone());
-        }
-        output_ranges
-    }
-}
-
-fn solve(file: &str) -> u64 {
-    let lines = file.lines().collect::<Vec<_>>();
-
-    let seed_ranges: Vec<Range<u64>> = lines[0]
-        .split(":")
-        .nth(1)
-        .unwrap()
-        .split_whitespace()
-        .map(|s| s.parse::<u64>().unwrap())
-        .collect::<Vec<_>>()
-        .chunks(2)
-        .map(|w| w[0]..w[0] + w[1])
-        .collect::<Vec<_>>();
-
-    let mut conversion_maps = Vec::new();
-    let mut reading_map = false;
-    for line in lines[1..].iter() {
-        if line.is_empty() {
-            reading_map = false;
-            continue;
-        }
-
-        if !reading_map {
-            // Starting a new map
-            let parts = line
-                .split_whitespace()
-                .nth(0)
-                .unwrap()
-                .split("-to-")
-                .collect::<Vec<_>>();
-            assert_eq!(parts.len(), 2);
-            let from = AlmanacType::from(parts[0]);
-            let to = AlmanacType::from(parts[1]);
-            conversion_maps.push(ConversionMap {
-                from,
-                to,
-                map: Vec::new(),
-            });
-            reading_map = true;
-            continue;
-        }
-
-        if reading_map {
-            // Reading conversion values
-            conversion_maps
-                .last_mut()
-                .unwrap()
-                .map
-                .push(ConversionValue::from(*line));
-        }
-    }
-
-    // Assume the conversion maps are in the correct order
-    conversion_maps
-        .iter()
-        .fold(seed_ranges, |ranges, map| map.convert(ranges))
+use day_5::{target_category, Almanac, AlmanacError, PreimageRange, TrackedRange};
+use std::time::Instant;
+
+/// The lowest `target` value any seed reaches, and the seed that reaches it.
+fn lowest_with_seed(almanac: &Almanac, target: &str) -> Result<(u64, u64), AlmanacError> {
+    let seeds = almanac
+        .seed_ranges()?
+        .into_iter()
+        .map(TrackedRange::new)
+        .collect();
+
+    almanac
+        .convert("seed", target, seeds)?
         .iter()
         // .inspect(|r| println!("{:?}", r))
-        .map(|r| r.start)
+        .map(|t| (t.range.start, t.origin))
         .min()
-        .unwrap()
+        .ok_or(AlmanacError::NoSeeds)
+}
+
+/// The seeds whose `target` value is below `limit`, each tracking the value its first seed
+/// ends up at.
+fn seeds_below(
+    almanac: &Almanac,
+    target: &str,
+    limit: u64,
+) -> Result<Vec<PreimageRange>, AlmanacError> {
+    let seeds = almanac.seed_ranges()?;
+
+    let mut found = almanac
+        .preimage("seed", target, vec![PreimageRange::new(0..limit)])?
+        .iter()
+        .flat_map(|t| {
+            seeds.iter().filter_map(|s| {
+                let start = t.range.start.max(s.start);
+                let end = t.range.end.min(s.end);
+                (start < end).then(|| PreimageRange {
+                    range: start..end,
+                    image: t.image_of(start),
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    found.sort_by_key(|t| t.range.start);
+    Ok(found)
 }
 
 fn main() {
     let input = include_str!("input.txt");
-    println!("Starting solution for part 1");
+    let target = target_category();
+    let args = std::env::args().collect::<Vec<_>>();
+    let below = args
+        .iter()
+        .position(|a| a == "--below")
+        .and_then(|i| args.get(i + 1))
+        .map(|n| n.parse::<u64>().unwrap());
+
+    println!("Starting solution for part 2");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = Almanac::parse(input).and_then(|almanac| {
+        let lowest = lowest_with_seed(&almanac, &target)?;
+        let below = match below {
+            Some(limit) => Some(seeds_below(&almanac, &target, limit)?),
+            None => None,
+        };
+        Ok((lowest, below))
+    });
+    let ((result, seed), below) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let t1 = Instant::now();
 
+    if let Some(below) = below {
+        for t in below {
+            println!("Seeds {:?} reach {} {}..", t.range, target, t.image);
+        }
+    }
     println!("Result: {}", result);
+    println!("Seed: {}", seed);
     println!("Time: {:?}", (t1 - t0).as_micros());
 }
 
@@ -202,6 +88,10 @@ fn main() {
 mod tests {
     use super::*;
 
+    fn solve(file: &str, target: &str) -> Result<u64, AlmanacError> {
+        lowest_with_seed(&Almanac::parse(file)?, target).map(|(value, _)| value)
+    }
+
     #[test]
     fn test_whole_part() {
         let input = "seeds: 79 14 55 13
@@ -237,7 +127,7 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!(solve(input), 46);
+        assert_eq!(solve(input, "location"), Ok(46));
     }
 
     #[test]
@@ -250,52 +140,185 @@ humidity-to-location map:
     #[test]
     fn test_range_conv_end_1() {
         let input = "seeds: 10 10 \n\nseed-to-soil map:\n 35 15 10";
-        assert_eq!(solve(input), 10);
+        assert_eq!(solve(input, "soil"), Ok(10));
     }
     #[test]
     fn test_range_conv_end_2() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 55 10";
-        assert_eq!(solve(input), 5);
+        assert_eq!(solve(input, "soil"), Ok(5));
     }
     #[test]
     fn test_range_conv_inclusive_1() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 55 2";
-        assert_eq!(solve(input), 5);
+        assert_eq!(solve(input, "soil"), Ok(5));
     }
     #[test]
     fn test_range_conv_inclusive_2() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 95 55 2";
-        assert_eq!(solve(input), 50);
+        assert_eq!(solve(input, "soil"), Ok(50));
     }
     #[test]
     fn test_range_inclusive_1() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 85 45 20";
-        assert_eq!(solve(input), 90);
+        assert_eq!(solve(input, "soil"), Ok(90));
     }
     #[test]
     fn test_range_inclusive_2() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 45 20";
-        assert_eq!(solve(input), 10);
+        assert_eq!(solve(input, "soil"), Ok(10));
     }
     #[test]
     fn test_range_conv_start_1() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 85 45 10";
-        assert_eq!(solve(input), 55);
+        assert_eq!(solve(input, "soil"), Ok(55));
     }
     #[test]
     fn test_range_conv_start_2() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 45 10";
-        assert_eq!(solve(input), 10);
+        assert_eq!(solve(input, "soil"), Ok(10));
     }
 
     #[test]
     fn test_range_outside_1() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 20 10";
-        assert_eq!(solve(input), 50);
+        assert_eq!(solve(input, "soil"), Ok(50));
     }
     #[test]
     fn test_range_outside_2() {
         let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 80 10";
-        assert_eq!(solve(input), 50);
+        assert_eq!(solve(input, "soil"), Ok(50));
+    }
+
+    #[test]
+    fn test_range_touching_end() {
+        let input = "seeds: 50 10 \n\nseed-to-soil map:\n 5 60 10";
+        assert_eq!(solve(input, "soil"), Ok(50));
+    }
+
+    #[test]
+    fn test_target_by_name() {
+        let input = "seeds: 79 14 55 13
+
+humidity-to-location map:
+60 56 37
+56 93 4
+
+soil-to-fertilizer map:
+0 15 37
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+fertilizer-to-humidity map:
+1 0 69";
+        // seed 55 -> soil 57 -> fertilizer 57 -> humidity 58
+        assert_eq!(solve(input, "humidity"), Ok(58));
+        // seed 91 -> soil 93 -> fertilizer 93 -> humidity 93 -> location 56
+        assert_eq!(solve(input, "location"), Ok(56));
+    }
+
+    #[test]
+    fn test_missing_map() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n 50 98 2\n\nwater-to-light map:\n 88 18 7";
+        assert_eq!(
+            solve(input, "light"),
+            Err(AlmanacError::MissingMap {
+                from: "seed".to_string(),
+                to: "light".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_duplicate_map() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n 50 98 2\n\nseed-to-soil map:\n 88 18 7";
+        assert_eq!(
+            solve(input, "soil"),
+            Err(AlmanacError::DuplicateMap {
+                from: "seed".to_string(),
+                to: "soil".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_odd_seed_count() {
+        let input = "seeds: 79 14 55\n\nseed-to-soil map:\n 50 98 2";
+        assert_eq!(
+            solve(input, "soil"),
+            Err(AlmanacError::OddSeedCount { count: 3 })
+        );
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n 50 10 20\n 0 25 5";
+        assert_eq!(
+            solve(input, "soil"),
+            Err(AlmanacError::OverlappingRanges {
+                from: "seed".to_string(),
+                to: "soil".to_string(),
+                first: 10..30,
+                second: 25..30
+            })
+        );
+    }
+
+    #[test]
+    fn test_winning_seed() {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        let almanac = Almanac::parse(input).unwrap();
+        assert_eq!(lowest_with_seed(&almanac, "location"), Ok((46, 82)));
+        assert_eq!(
+            seeds_below(&almanac, "location", 47),
+            Ok(vec![PreimageRange {
+                range: 82..83,
+                image: 46
+            }])
+        );
+
+        let lowest = seeds_below(&almanac, "location", 60).unwrap();
+        assert!(!lowest.is_empty());
+        let chain = almanac.chain("seed", "location").unwrap();
+        for t in lowest {
+            for seed in t.range.clone() {
+                let location = chain.iter().fold(seed, |acc, map| map.convert_value(acc));
+                assert!(location < 60);
+                assert_eq!(location, t.image_of(seed));
+            }
+        }
     }
 }
@@ -0,0 +1,451 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    ops::Range,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlmanacError {
+    /// Two maps convert between the same pair of categories.
+    DuplicateMap { from: String, to: String },
+    /// No chain of maps leads from one category to the other.
+    MissingMap { from: String, to: String },
+    /// More than one map out of `category` leads on to `to`, so the chain is not unique.
+    AmbiguousChain {
+        category: String,
+        to: String,
+        options: Vec<String>,
+    },
+    /// The almanac lists no seeds to convert.
+    NoSeeds,
+    /// Seed ranges come in start and length pairs, but there is an odd number of values.
+    OddSeedCount { count: usize },
+    /// A line that is not a seed list, map header or conversion. `line` counts from 1.
+    Malformed { line: usize, text: String },
+    /// Two source ranges inside one map overlap, so a value has two conversions.
+    OverlappingRanges {
+        from: String,
+        to: String,
+        first: Range<u64>,
+        second: Range<u64>,
+    },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::DuplicateMap { from, to } => {
+                write!(f, "Duplicate {}-to-{} map", from, to)
+            }
+            AlmanacError::MissingMap { from, to } => {
+                write!(f, "No chain of maps from {} to {}", from, to)
+            }
+            AlmanacError::AmbiguousChain {
+                category,
+                to,
+                options,
+            } => write!(
+                f,
+                "Several chains of maps lead from {} to {}, through {}",
+                category,
+                to,
+                options.join(" and ")
+            ),
+            AlmanacError::NoSeeds => write!(f, "The almanac lists no seeds"),
+            AlmanacError::OddSeedCount { count } => write!(
+                f,
+                "{} seed values do not make start and length pairs",
+                count
+            ),
+            AlmanacError::Malformed { line, text } => {
+                write!(f, "Malformed line {}: {}", line, text)
+            }
+            AlmanacError::OverlappingRanges {
+                from,
+                to,
+                first,
+                second,
+            } => write!(
+                f,
+                "Overlapping source ranges {:?} and {:?} in {}-to-{} map",
+                first, second, from, to
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConversionValue {
+    pub from_range: Range<u64>,
+    pub to_range: Range<u64>,
+}
+
+impl ConversionValue {
+    /// A `destination source length` row, or `None` if it is not one.
+    pub fn parse(row: &str) -> Option<Self> {
+        let nums = row
+            .split_whitespace()
+            .map(|s| s.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let [to, from, length] = nums[..] else {
+            return None;
+        };
+        Some(ConversionValue {
+            from_range: from..from.checked_add(length)?,
+            to_range: to..to.checked_add(length)?,
+        })
+    }
+
+    /// Converts a value, which may also be the exclusive end of `from_range`.
+    fn shift(&self, value: u64) -> u64 {
+        self.to_range.start + (value - self.from_range.start)
+    }
+
+    pub fn inverted(&self) -> ConversionValue {
+        ConversionValue {
+            from_range: self.to_range.clone(),
+            to_range: self.from_range.clone(),
+        }
+    }
+}
+
+/// A range of values together with the value its first element was converted from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedRange {
+    pub range: Range<u64>,
+    pub origin: u64,
+}
+
+impl TrackedRange {
+    pub fn new(range: Range<u64>) -> Self {
+        TrackedRange {
+            origin: range.start,
+            range,
+        }
+    }
+
+    /// The value that `value` in this range was converted from.
+    pub fn origin_of(&self, value: u64) -> u64 {
+        self.origin + (value - self.range.start)
+    }
+
+    fn slice(&self, range: Range<u64>) -> TrackedRange {
+        TrackedRange {
+            origin: self.origin_of(range.start),
+            range,
+        }
+    }
+}
+
+/// A range of values together with the value its first element converts into, as found by
+/// converting backwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreimageRange {
+    pub range: Range<u64>,
+    pub image: u64,
+}
+
+impl PreimageRange {
+    pub fn new(range: Range<u64>) -> Self {
+        PreimageRange {
+            image: range.start,
+            range,
+        }
+    }
+
+    /// The value that `value` in this range converts into.
+    pub fn image_of(&self, value: u64) -> u64 {
+        self.image + (value - self.range.start)
+    }
+
+    fn slice(&self, range: Range<u64>) -> PreimageRange {
+        PreimageRange {
+            image: self.image_of(range.start),
+            range,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConversionMap {
+    pub from: String,
+    pub to: String,
+    pub map: Vec<ConversionValue>,
+}
+
+impl ConversionMap {
+    pub fn convert_value(&self, value: u64) -> u64 {
+        self.map
+            .iter()
+            .find(|conv| conv.from_range.contains(&value))
+            .map_or(value, |conv| conv.shift(value))
+    }
+
+    pub fn convert(&self, starting_range: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        self.convert_tracked(starting_range.into_iter().map(TrackedRange::new).collect())
+            .into_iter()
+            .map(|t| t.range)
+            .collect()
+    }
+
+    /// Converts ranges forward, keeping track of where each output range came from.
+    pub fn convert_tracked(&self, starting_range: Vec<TrackedRange>) -> Vec<TrackedRange> {
+        let mut output_ranges = Vec::new();
+        let mut leftover_range = starting_range;
+
+        for conv in self.map.iter() {
+            let mut new_leftover_range = Vec::new();
+
+            for before in leftover_range.into_iter().filter(|t| !t.range.is_empty()) {
+                let start = before.range.start.max(conv.from_range.start);
+                let end = before.range.end.min(conv.from_range.end);
+                if start >= end {
+                    new_leftover_range.push(before);
+                    continue;
+                }
+
+                // Overlapping part is converted, the parts on either side are left over
+                output_ranges.push(TrackedRange {
+                    range: conv.shift(start)..conv.shift(end),
+                    origin: before.origin_of(start),
+                });
+                new_leftover_range.push(before.slice(before.range.start..start));
+                new_leftover_range.push(before.slice(end..before.range.end));
+            }
+            leftover_range = new_leftover_range;
+        }
+
+        output_ranges.extend(leftover_range.into_iter().filter(|t| !t.range.is_empty()));
+        output_ranges
+    }
+
+    /// Converts ranges backwards: every input value whose conversion lands in one of the
+    /// given ranges.
+    pub fn preimage(&self, ranges: Vec<PreimageRange>) -> Vec<PreimageRange> {
+        let inverted = self.map.iter().map(|c| c.inverted()).collect::<Vec<_>>();
+        let mut sources = self
+            .map
+            .iter()
+            .map(|c| c.from_range.clone())
+            .collect::<Vec<_>>();
+        sources.sort_by_key(|r| r.start);
+
+        let mut output_ranges = Vec::new();
+        for before in ranges.iter().filter(|t| !t.range.is_empty()) {
+            // Values inside a destination range came from the matching source range
+            for conv in inverted.iter() {
+                let start = before.range.start.max(conv.from_range.start);
+                let end = before.range.end.min(conv.from_range.end);
+                if start < end {
+                    output_ranges.push(PreimageRange {
+                        range: conv.shift(start)..conv.shift(end),
+                        image: before.image_of(start),
+                    });
+                }
+            }
+
+            // Values outside every source range map onto themselves
+            let mut start = before.range.start;
+            for source in sources.iter() {
+                if source.start > start {
+                    let end = source.start.min(before.range.end);
+                    output_ranges.push(before.slice(start..end));
+                }
+                start = start.max(source.end);
+                if start >= before.range.end {
+                    break;
+                }
+            }
+            output_ranges.push(before.slice(start..before.range.end.max(start)));
+        }
+
+        output_ranges.retain(|t| !t.range.is_empty());
+        output_ranges
+    }
+
+    fn check_overlaps(&self) -> Result<(), AlmanacError> {
+        let mut sorted = self.map.iter().map(|c| &c.from_range).collect::<Vec<_>>();
+        sorted.sort_by_key(|r| r.start);
+
+        match sorted.windows(2).find(|w| w[0].end > w[1].start) {
+            Some(w) => Err(AlmanacError::OverlappingRanges {
+                from: self.from.clone(),
+                to: self.to.clone(),
+                first: w[0].clone(),
+                second: w[1].clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The seeds and every conversion map, keyed by the category they convert from.
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    maps: HashMap<String, Vec<ConversionMap>>,
+}
+
+impl Almanac {
+    pub fn parse(file: &str) -> Result<Self, AlmanacError> {
+        let lines = file.lines().collect::<Vec<_>>();
+        let malformed = |i: usize| AlmanacError::Malformed {
+            line: i + 1,
+            text: lines[i].to_string(),
+        };
+
+        let Some(first) = lines.first() else {
+            return Err(AlmanacError::NoSeeds);
+        };
+        let seeds = first
+            .strip_prefix("seeds:")
+            .ok_or_else(|| malformed(0))?
+            .split_whitespace()
+            .map(|s| s.parse::<u64>().map_err(|_| malformed(0)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut conversion_maps: Vec<ConversionMap> = Vec::new();
+        let mut reading_map = false;
+        for (i, line) in lines.iter().enumerate().skip(1) {
+            if line.is_empty() {
+                reading_map = false;
+                continue;
+            }
+
+            if !reading_map {
+                // Starting a new map
+                let (from, to) = line
+                    .strip_suffix(" map:")
+                    .and_then(|name| name.split_once("-to-"))
+                    .ok_or_else(|| malformed(i))?;
+                conversion_maps.push(ConversionMap {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    map: Vec::new(),
+                });
+                reading_map = true;
+                continue;
+            }
+
+            // Reading conversion values
+            let value = ConversionValue::parse(line).ok_or_else(|| malformed(i))?;
+            conversion_maps.last_mut().unwrap().map.push(value);
+        }
+
+        let mut maps: HashMap<String, Vec<ConversionMap>> = HashMap::new();
+        for map in conversion_maps {
+            map.check_overlaps()?;
+            let edges = maps.entry(map.from.clone()).or_default();
+            if edges.iter().any(|m| m.to == map.to) {
+                return Err(AlmanacError::DuplicateMap {
+                    from: map.from,
+                    to: map.to,
+                });
+            }
+            edges.push(map);
+        }
+
+        Ok(Almanac { seeds, maps })
+    }
+
+    /// The seeds read as start and length pairs, as in part 2.
+    pub fn seed_ranges(&self) -> Result<Vec<Range<u64>>, AlmanacError> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err(AlmanacError::OddSeedCount {
+                count: self.seeds.len(),
+            });
+        }
+        Ok(self.seeds.chunks(2).map(|w| w[0]..w[0] + w[1]).collect())
+    }
+
+    /// The sequence of maps converting `from` into `to`, which has to be the only one.
+    pub fn chain(&self, from: &str, to: &str) -> Result<Vec<&ConversionMap>, AlmanacError> {
+        let missing = || AlmanacError::MissingMap {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+
+        // Every category with some chain of maps into `to`, searching backwards
+        let mut leads_to = HashSet::from([to]);
+        let mut queue = VecDeque::from([to]);
+        while let Some(category) = queue.pop_front() {
+            for map in self.maps.values().flatten() {
+                if map.to == category && leads_to.insert(&map.from) {
+                    queue.push_back(&map.from);
+                }
+            }
+        }
+        if !leads_to.contains(from) {
+            return Err(missing());
+        }
+
+        // Going forwards, every category on the way has to have a single map that leads on.
+        // A cycle always leaves some category with two, so this ends.
+        let mut chain = Vec::new();
+        let mut current = from;
+        while current != to {
+            let options = self
+                .maps
+                .get(current)
+                .into_iter()
+                .flatten()
+                .filter(|m| leads_to.contains(m.to.as_str()))
+                .collect::<Vec<_>>();
+            match options[..] {
+                [] => return Err(missing()),
+                [map] => {
+                    chain.push(map);
+                    current = &map.to;
+                }
+                _ => {
+                    let mut options = options.iter().map(|m| m.to.clone()).collect::<Vec<_>>();
+                    options.sort();
+                    return Err(AlmanacError::AmbiguousChain {
+                        category: current.to_string(),
+                        to: to.to_string(),
+                        options,
+                    });
+                }
+            }
+        }
+        Ok(chain)
+    }
+
+    /// Converts ranges of `from` values to `to` values, keeping their origin.
+    pub fn convert(
+        &self,
+        from: &str,
+        to: &str,
+        ranges: Vec<TrackedRange>,
+    ) -> Result<Vec<TrackedRange>, AlmanacError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(ranges, |ranges, map| map.convert_tracked(ranges)))
+    }
+
+    /// All `from` values that convert into the given `to` ranges, with the `to` value each
+    /// one ends up at.
+    pub fn preimage(
+        &self,
+        from: &str,
+        to: &str,
+        ranges: Vec<PreimageRange>,
+    ) -> Result<Vec<PreimageRange>, AlmanacError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .rev()
+            .fold(ranges, |ranges, map| map.preimage(ranges)))
+    }
+}
+
+/// Reads `--to <category>` from the command line, defaulting to `location`.
+pub fn target_category() -> String {
+    let args = std::env::args().collect::<Vec<_>>();
+    args.iter()
+        .position(|a| a == "--to")
+        .and_then(|i| args.get(i + 1))
+        .cloned()
+        .unwrap_or_else(|| "location".to_string())
+}
@@ -1,33 +1,8 @@
+use day_6::{margin_of_error, parse_races};
 use std::time::Instant;
 
-fn solve(file: &str) -> i32 {
-    // First line has times,
-    // Second line has distances
-    let mut lines = file.lines().collect::<Vec<_>>();
-    let times = lines[0]
-        .split(':')
-        .nth(1)
-        .unwrap()
-        .split_whitespace()
-        .map(|x| x.parse::<i32>().unwrap())
-        .collect::<Vec<_>>();
-    let dist = lines[1]
-        .split(':')
-        .nth(1)
-        .unwrap()
-        .split_whitespace()
-        .map(|x| x.parse::<i32>().unwrap())
-        .collect::<Vec<_>>();
-
-    assert_eq!(times.len(), dist.len());
-    let mut prod = 1;
-    for i in 0..times.len() {
-        prod *= (0..times[i])
-            .map(|t_hold| t_hold * (times[i] - t_hold))
-            .filter(|d| d > &dist[i])
-            .count();
-    }
-    prod as i32
+fn solve(file: &str) -> u64 {
+    margin_of_error(&parse_races(file))
 }
 
 fn main() {
@@ -0,0 +1,155 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
+}
+
+fn line_values(line: &str) -> Vec<&str> {
+    line.split(':').nth(1).unwrap().split_whitespace().collect()
+}
+
+/// Every column of the sheet is a separate race.
+pub fn parse_races(file: &str) -> Vec<Race> {
+    // First line has times,
+    // Second line has distances
+    let lines = file.lines().collect::<Vec<_>>();
+    let times = line_values(lines[0]);
+    let dist = line_values(lines[1]);
+    assert_eq!(times.len(), dist.len());
+
+    times
+        .iter()
+        .zip(dist.iter())
+        .map(|(t, d)| Race {
+            time: t.parse().unwrap(),
+            record: d.parse().unwrap(),
+        })
+        .collect()
+}
+
+/// The sheet is a single race, with the spaces between the digits ignored.
+pub fn parse_joined_race(file: &str) -> Race {
+    let lines = file.lines().collect::<Vec<_>>();
+    Race {
+        time: line_values(lines[0]).join("").parse().unwrap(),
+        record: line_values(lines[1]).join("").parse().unwrap(),
+    }
+}
+
+/// Largest `r` with `r * r <= n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from an estimate that is never below the root
+    let mut x = 1_u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Number of hold times that beat the record.
+///
+/// Holding for `h` travels `h * (time - h)`, which beats the record exactly when
+/// `(2h - time)^2 < time^2 - 4 * record`. So only `|2h - time|` has to be bounded, and it
+/// always has the same parity as `time`.
+pub fn ways_to_win(race: &Race) -> u64 {
+    let time = race.time as u128;
+    let discriminant = match (time * time).checked_sub(4 * race.record as u128) {
+        Some(d) if d > 0 => d,
+        _ => return 0,
+    };
+
+    // Largest allowed |2h - time|, a perfect square only ties the record
+    let root = isqrt(discriminant);
+    let max_offset = match root * root == discriminant {
+        true => root - 1,
+        false => root,
+    };
+
+    match max_offset % 2 == time % 2 {
+        true => max_offset as u64 + 1,
+        false => max_offset as u64,
+    }
+}
+
+/// Brute force version of `ways_to_win`, trying every hold time.
+pub fn ways_to_win_brute(race: &Race) -> u64 {
+    (0..=race.time)
+        .map(|t_hold| t_hold as u128 * (race.time - t_hold) as u128)
+        .filter(|d| *d > race.record as u128)
+        .count() as u64
+}
+
+pub fn margin_of_error(races: &[Race]) -> u64 {
+    races.iter().map(ways_to_win).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..10_000_u128 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "isqrt({}) = {}", n, r);
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(isqrt((1 << 106) - 1), (1 << 53) - 1);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // Small xorshift, so the cases are the same on every run
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = |max: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % max
+        };
+
+        for _ in 0..2000 {
+            let time = next(300);
+            let hold = next(time + 1);
+            // Half of the records are ties with a hold time, giving a perfect square
+            let record = match next(2) {
+                0 => hold * (time - hold),
+                _ => next(time * time / 4 + 2),
+            };
+            let race = Race { time, record };
+            assert_eq!(ways_to_win(&race), ways_to_win_brute(&race), "{:?}", race);
+        }
+    }
+
+    #[test]
+    fn test_record_tie() {
+        // Holding 5 travels exactly 25, which does not beat the record
+        let race = Race {
+            time: 10,
+            record: 24,
+        };
+        assert_eq!(ways_to_win(&race), 1);
+        let race = Race {
+            time: 10,
+            record: 25,
+        };
+        assert_eq!(ways_to_win(&race), 0);
+    }
+
+    #[test]
+    fn test_large_time() {
+        // time * time is above 2^53, where an f64 no longer holds every integer exactly
+        let time = (1_u64 << 32) + 1;
+        let race = Race {
+            time,
+            record: (time / 2) * (time - time / 2) - 1,
+        };
+        assert_eq!(ways_to_win(&race), 2);
+    }
+}
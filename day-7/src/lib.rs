@@ -0,0 +1,184 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum HandType {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+/// How hands are read and ranked.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    /// Cards from weakest to strongest.
+    pub order: Vec<char>,
+    /// Cards that count as whatever makes the hand strongest.
+    pub wildcards: Vec<char>,
+    pub hand_size: usize,
+}
+
+impl Rules {
+    pub fn new(order: &str, wildcards: &str, hand_size: usize) -> Self {
+        let rules = Rules {
+            order: order.chars().collect(),
+            wildcards: wildcards.chars().collect(),
+            hand_size,
+        };
+        assert!(
+            rules.card_bits() * hand_size as u32 + 3 <= 128,
+            "Hands of {} out of {} cards do not fit in a sort key",
+            hand_size,
+            rules.order.len()
+        );
+        rules
+    }
+
+    /// Part 1: plain Camel Cards.
+    pub fn standard() -> Self {
+        Rules::new("23456789TJQKA", "", 5)
+    }
+
+    /// Part 2: `J` is a joker, the weakest card but wild when picking the hand type.
+    pub fn jokers() -> Self {
+        Rules::new("J23456789TQKA", "J", 5)
+    }
+
+    fn card_bits(&self) -> u32 {
+        usize::BITS - (self.order.len().max(2) - 1).leading_zeros()
+    }
+
+    fn rank_of(&self, card: char) -> u32 {
+        self.order
+            .iter()
+            .position(|&x| x == card)
+            .unwrap_or_else(|| panic!("Unknown card: {}", card)) as u32
+    }
+
+    fn hand_type(&self, cards: &[u32]) -> HandType {
+        let mut card_counts = vec![0_u32; self.order.len()];
+        let mut joker_count = 0;
+        for &c in cards.iter() {
+            match self.wildcards.contains(&self.order[c as usize]) {
+                true => joker_count += 1,
+                false => card_counts[c as usize] += 1,
+            }
+        }
+        card_counts.sort_unstable_by(|a, b| b.cmp(a));
+        card_counts.push(0);
+        // Add the joker count to the highest card count
+        card_counts[0] += joker_count;
+
+        match (card_counts[0], card_counts[1]) {
+            (5.., _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, 2..) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, 2..) => HandType::TwoPair,
+            (2, _) => HandType::Pair,
+            _ => HandType::HighCard,
+        }
+    }
+}
+
+/// A hand with its type and sort key worked out once while parsing.
+#[derive(Clone)]
+pub struct Hand {
+    pub cards: String,
+    pub bid: u32,
+    hand_type: HandType,
+    /// The hand type followed by every card rank, packed so hands sort by this alone.
+    key: u128,
+}
+
+impl Hand {
+    pub fn parse(line: &str, rules: &Rules) -> Hand {
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        let hand = parts[0];
+        let bid = parts[1].parse::<u32>().unwrap();
+
+        let cards = hand.chars().map(|c| rules.rank_of(c)).collect::<Vec<_>>();
+        assert_eq!(cards.len(), rules.hand_size, "Wrong hand size: {}", hand);
+
+        let hand_type = rules.hand_type(&cards);
+        let key = cards.iter().fold(hand_type as u128, |key, &c| {
+            (key << rules.card_bits()) | c as u128
+        });
+
+        Hand {
+            cards: hand.to_string(),
+            bid,
+            hand_type,
+            key,
+        }
+    }
+
+    pub fn get_type(&self) -> HandType {
+        self.hand_type
+    }
+}
+
+impl fmt::Debug for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.cards, self.bid)
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Hand {}
+
+/// All hands, weakest first, so the rank of a hand is its index plus one.
+pub fn ranked_hands(file: &str, rules: &Rules) -> Vec<Hand> {
+    let mut hands = file
+        .lines()
+        .map(|x| Hand::parse(x, rules))
+        .collect::<Vec<_>>();
+    hands.sort();
+    hands
+}
+
+pub fn total_winnings(hands: &[Hand]) -> i64 {
+    hands
+        .iter()
+        .enumerate()
+        .fold(0, |acc, (i, h)| acc + (i + 1) as i64 * h.bid as i64)
+}
+
+/// One line per hand with its type, rank and winnings, for `--explain`.
+pub fn explain(hands: &[Hand]) -> String {
+    hands
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            format!(
+                "{} {:?} rank {} bid {} wins {}\n",
+                h.cards,
+                h.hand_type,
+                i + 1,
+                h.bid,
+                (i + 1) as i64 * h.bid as i64
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,146 @@
+use day_8::{matchers_from_args, parse, report, to_dot};
+use std::time::Instant;
+
+fn main() {
+    let input = include_str!("input.txt");
+    let args = std::env::args().collect::<Vec<_>>();
+    let dot_file = args
+        .iter()
+        .position(|a| a == "--dot")
+        .and_then(|i| args.get(i + 1));
+
+    let (start, end) = matchers_from_args("suffix:A", "suffix:Z");
+
+    println!("Analysing network");
+    let t0 = Instant::now();
+    let (instructions, network) = parse(input);
+    let result = report(&instructions, &network, &start, &end);
+    let duration = t0.elapsed();
+    print!("{}", result);
+    println!("Time: {:?}", duration);
+
+    if let Some(dot_file) = dot_file {
+        std::fs::write(dot_file, to_dot(&network, &start, &end)).unwrap();
+        println!("Written DOT graph to {}", dot_file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use day_8::{strongly_connected_components, GhostWalk, Network, NodeMatcher};
+
+    const INPUT: &str = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+
+    fn ghost(network: &Network, instructions: &[day_8::Direction], start: &str) -> GhostWalk {
+        let ends = network.mask(&"suffix:Z".into());
+        GhostWalk::new(network.id(start).unwrap(), instructions, network, &ends)
+    }
+
+    #[test]
+    fn test_components() {
+        let (_, network) = parse(INPUT);
+        let components = strongly_connected_components(&network)
+            .iter()
+            .map(|c| c.iter().map(|&id| network.name(id)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(components.len(), 5);
+        assert_eq!(components[0], vec!["22B", "22C", "22Z"]);
+        assert_eq!(components[1], vec!["11B", "11Z"]);
+    }
+
+    #[test]
+    fn test_ghost_walks() {
+        let (instructions, network) = parse(INPUT);
+        let walk = ghost(&network, &instructions, "11A");
+        assert_eq!(walk.first_end(), Some(2));
+        assert_eq!((walk.cycle_start, walk.cycle_length), (1, 2));
+        assert!(walk.lcm_compatible());
+
+        // 22Z is hit every 3 steps, not a multiple of the 2 instructions, but still regularly
+        let walk = ghost(&network, &instructions, "22A");
+        assert_eq!(walk.first_end(), Some(3));
+        assert_eq!((walk.cycle_start, walk.cycle_length), (1, 6));
+        assert_eq!(walk.end_steps, vec![3, 6]);
+        assert!(walk.lcm_compatible());
+
+        let (start, end) = ("suffix:A".into(), "suffix:Z".into());
+        let report = report(&instructions, &network, &start, &end);
+        assert!(report.contains(
+            "11A reaches [11Z], first end after Some(2) steps (multiple of 2 instructions: true)"
+        ));
+        assert!(report.contains(
+            "22A reaches [22Z], first end after Some(3) steps (multiple of 2 instructions: false)"
+        ));
+        assert!(report.ends_with("LCM shortcut valid: true\n"));
+    }
+
+    #[test]
+    fn test_lcm_not_compatible() {
+        // The ghost reaches an end after 1 step, but only every 2 steps after that
+        let input = "L\n\nAAA = (BBZ, AAA)\nBBZ = (CCC, CCC)\nCCC = (BBZ, BBZ)";
+        let (instructions, network) = parse(input);
+        let walk = ghost(&network, &instructions, "AAA");
+        assert_eq!(walk.end_steps, vec![1]);
+        assert_eq!((walk.cycle_start, walk.cycle_length), (1, 2));
+        assert!(!walk.lcm_compatible());
+
+        let (start, end) = ("suffix:A".into(), "suffix:Z".into());
+        assert!(
+            report(&instructions, &network, &start, &end).ends_with("LCM shortcut valid: false\n")
+        );
+    }
+
+    #[test]
+    fn test_dot() {
+        let (_, network) = parse("LR\n\nAAA = (ZZZ, BBB)\nBBB = (BBB, BBB)\nZZZ = (AAA, ZZZ)");
+        let (start, end): (NodeMatcher, NodeMatcher) = ("AAA".into(), "ZZZ".into());
+        assert_eq!(
+            to_dot(&network, &start, &end),
+            "digraph network {
+  \"AAA\" [style=filled, fillcolor=green];
+  \"ZZZ\" [style=filled, fillcolor=red];
+  \"AAA\" -> \"ZZZ\" [label=\"L\"];
+  \"AAA\" -> \"BBB\" [label=\"R\"];
+  \"BBB\" -> \"BBB\" [label=\"LR\"];
+  \"ZZZ\" -> \"AAA\" [label=\"L\"];
+  \"ZZZ\" -> \"ZZZ\" [label=\"R\"];
+}
+"
+        );
+
+        let (_, network) = parse("L\n\nA\"B = (C\\D, C\\D)\nC\\D = (C\\D, A\"B)");
+        assert_eq!(
+            to_dot(&network, &"A\"B".into(), &"C\\D".into()),
+            r#"digraph network {
+  "A\"B" [style=filled, fillcolor=green];
+  "C\\D" [style=filled, fillcolor=red];
+  "A\"B" -> "C\\D" [label="LR"];
+  "C\\D" -> "C\\D" [label="L"];
+  "C\\D" -> "A\"B" [label="R"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_node_matchers() {
+        let pattern = NodeMatcher::from("pattern:1?Z*");
+        assert!(pattern.matches("11Z"));
+        assert!(pattern.matches("1BZEND"));
+        assert!(!pattern.matches("1Z"));
+        assert!(!pattern.matches("21Z"));
+        assert!(NodeMatcher::from("suffix:Z").matches("LONGNAMEZ"));
+        assert!(NodeMatcher::from("AAA,B").matches("B"));
+        assert!(!NodeMatcher::from("AAA,B").matches("BB"));
+    }
+}
@@ -1,75 +1,25 @@
-use std::{collections::HashMap, time::Instant};
+use day_8::{matchers_from_args, solve_walks, NodeMatcher, SolveError};
+use std::time::Instant;
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-enum Direction {
-    Left,
-    Right,
-}
-
-impl From<char> for Direction {
-    fn from(c: char) -> Self {
-        match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("Invalid direction"),
-        }
-    }
-}
+const START: &str = "AAA";
+const END: &str = "ZZZ";
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
-struct Location([char; 3]);
-
-impl From<&str> for Location {
-    fn from(s: &str) -> Self {
-        let mut chars = s.chars();
-        let first = chars.next().unwrap();
-        let second = chars.next().unwrap();
-        let third = chars.next().unwrap();
-        Location([first, second, third])
-    }
-}
-
-fn solve(file: &str) -> i64 {
-    let lines = file.lines().collect::<Vec<_>>();
-    //parse the instructions
-    let instructions = lines[0].chars().map(Direction::from).collect::<Vec<_>>();
-
-    //parse the map
-    let mut map = HashMap::<Location, [Location; 2]>::new();
-    for line in lines[2..].iter() {
-        let mut parts = line.split(" = ");
-        let key = parts.next().unwrap();
-        let values = parts.next().unwrap();
-        let values: Vec<Location> = values[1..values.len() - 1]
-            .split(", ")
-            .map(|loc| loc.into())
-            .collect();
-        map.insert(key.into(), [values[0], values[1]]);
-    }
-
-    //Walk the map until it reaches target
-    let mut current = Location(['A', 'A', 'A']);
-    let target = Location(['Z', 'Z', 'Z']);
-    let mut steps = 0;
-
-    while current != target {
-        let direction = instructions[steps % instructions.len()];
-        let next = match direction {
-            Direction::Left => map[&current][0],
-            Direction::Right => map[&current][1],
-        };
-        current = next;
-        steps += 1;
-    }
-
-    steps as i64
+fn solve(file: &str, start: &NodeMatcher, end: &NodeMatcher) -> Result<i64, SolveError> {
+    solve_walks(file, start, end)
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let (start, end) = matchers_from_args(START, END);
     println!("Starting solution");
     let t0 = Instant::now();
-    let result = solve(input);
+    let result = match solve(input, &start, &end) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     let duration = t0.elapsed();
     println!("Result: {}", result);
     println!("Time: {:?}", duration);
@@ -90,7 +40,7 @@ DDD = (DDD, DDD)
 EEE = (EEE, EEE)
 GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(solve(input), 2);
+        assert_eq!(solve(input, &START.into(), &END.into()), Ok(2));
     }
 
     #[test]
@@ -100,6 +50,12 @@ ZZZ = (ZZZ, ZZZ)";
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(solve(input), 6);
+        assert_eq!(solve(input, &START.into(), &END.into()), Ok(6));
+    }
+
+    #[test]
+    fn test_long_names() {
+        let input = "LR\n\nSTART = (MIDDLE, X)\nX = (X, X)\nMIDDLE = (X, FINISH)\nFINISH = (X, X)";
+        assert_eq!(solve(input, &"START".into(), &"FINISH".into()), Ok(2));
     }
 }
@@ -0,0 +1,533 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Write};
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+impl From<char> for Direction {
+    fn from(c: char) -> Self {
+        match c {
+            'L' => Direction::Left,
+            'R' => Direction::Right,
+            _ => panic!("Invalid direction"),
+        }
+    }
+}
+
+/// The nodes of the map, with their names interned into dense ids so that walking is an
+/// array lookup.
+#[derive(Debug, Default)]
+pub struct Network {
+    names: Vec<String>,
+    ids: HashMap<String, u32>,
+    edges: Vec<Option<[u32; 2]>>,
+}
+
+impl Network {
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.edges.push(None);
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    /// Both nodes a node leads to, left first.
+    pub fn neighbours(&self, id: u32) -> [u32; 2] {
+        self.edges[id as usize].unwrap()
+    }
+
+    pub fn next(&self, id: u32, direction: Direction) -> u32 {
+        match direction {
+            Direction::Left => self.neighbours(id)[0],
+            Direction::Right => self.neighbours(id)[1],
+        }
+    }
+
+    /// Every node id, ordered by name.
+    pub fn sorted_ids(&self) -> Vec<u32> {
+        let mut ids = (0..self.len() as u32).collect::<Vec<_>>();
+        ids.sort_by_key(|&id| self.name(id));
+        ids
+    }
+
+    /// The ids of all matching nodes, ordered by name.
+    pub fn select(&self, matcher: &NodeMatcher) -> Vec<u32> {
+        self.sorted_ids()
+            .into_iter()
+            .filter(|&id| matcher.matches(self.name(id)))
+            .collect()
+    }
+
+    /// For every node id whether it matches.
+    pub fn mask(&self, matcher: &NodeMatcher) -> Vec<bool> {
+        self.names.iter().map(|n| matcher.matches(n)).collect()
+    }
+}
+
+pub fn parse(file: &str) -> (Vec<Direction>, Network) {
+    let lines = file.lines().collect::<Vec<_>>();
+    //parse the instructions
+    let instructions = lines[0].chars().map(Direction::from).collect::<Vec<_>>();
+
+    //parse the map
+    let mut network = Network::default();
+    for line in lines[2..].iter() {
+        let (key, values) = line.split_once(" = ").unwrap();
+        let (left, right) = values[1..values.len() - 1].split_once(", ").unwrap();
+        let id = network.intern(key);
+        let left = network.intern(left);
+        let right = network.intern(right);
+        network.edges[id as usize] = Some([left, right]);
+    }
+
+    if let Some(missing) = network.edges.iter().position(|e| e.is_none()) {
+        panic!("Node {} is never defined", network.names[missing]);
+    }
+    (instructions, network)
+}
+
+/// Selects nodes by name.
+///
+/// Written as `suffix:Z` for names ending in `Z`, `pattern:1?Z*` for a pattern where `?`
+/// matches any one character and `*` any number of them, or a comma separated list of
+/// exact names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeMatcher {
+    Names(Vec<String>),
+    Suffix(String),
+    Pattern(String),
+}
+
+impl From<&str> for NodeMatcher {
+    fn from(s: &str) -> Self {
+        if let Some(suffix) = s.strip_prefix("suffix:") {
+            NodeMatcher::Suffix(suffix.to_string())
+        } else if let Some(pattern) = s.strip_prefix("pattern:") {
+            NodeMatcher::Pattern(pattern.to_string())
+        } else {
+            NodeMatcher::Names(s.split(',').map(|n| n.to_string()).collect())
+        }
+    }
+}
+
+impl NodeMatcher {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NodeMatcher::Names(names) => names.iter().any(|n| n == name),
+            NodeMatcher::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            NodeMatcher::Pattern(pattern) => {
+                let pattern = pattern.chars().collect::<Vec<_>>();
+                let name = name.chars().collect::<Vec<_>>();
+                glob_matches(&pattern, &name)
+            }
+        }
+    }
+}
+
+fn glob_matches(pattern: &[char], name: &[char]) -> bool {
+    // matched[j]: whether the pattern so far matches the first j characters of the name
+    let mut matched = vec![false; name.len() + 1];
+    matched[0] = true;
+    for &p in pattern {
+        let mut next = vec![false; name.len() + 1];
+        if p == '*' {
+            next[0] = matched[0];
+            for j in 1..=name.len() {
+                next[j] = matched[j] || next[j - 1];
+            }
+        } else {
+            for j in 1..=name.len() {
+                next[j] = matched[j - 1] && (p == '?' || p == name[j - 1]);
+            }
+        }
+        matched = next;
+    }
+    matched[name.len()]
+}
+
+/// Reads `--start` and `--end` node matchers from the command line, see `NodeMatcher`.
+pub fn matchers_from_args(start: &str, end: &str) -> (NodeMatcher, NodeMatcher) {
+    let args = std::env::args().collect::<Vec<_>>();
+    let get = |flag: &str, default: &str| {
+        let value = args
+            .iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map_or(default, |v| v.as_str());
+        NodeMatcher::from(value)
+    };
+    (get("--start", start), get("--end", end))
+}
+
+/// Steps to walk from `start` until standing on an end node, or `None` if the walk loops
+/// forever without reaching one.
+pub fn steps_to_end(
+    instructions: &[Direction],
+    network: &Network,
+    start: u32,
+    ends: &[bool],
+) -> Option<usize> {
+    // After this many steps some (node, instruction) state has repeated
+    let max_steps = network.len() * instructions.len();
+    let mut current = start;
+    for steps in 0..=max_steps {
+        if ends[current as usize] {
+            return Some(steps);
+        }
+        current = network.next(current, instructions[steps % instructions.len()]);
+    }
+    None
+}
+
+fn greatest_common_denominator(a: i64, b: i64) -> i64 {
+    let mut a = a;
+    let mut b = b;
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a
+}
+
+fn least_common_multiple(a: i64, b: i64) -> i64 {
+    a / greatest_common_denominator(a, b) * b
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// No node matches the start matcher.
+    NoStartNodes,
+    /// The walk from this start node loops forever without reaching an end node.
+    NeverEnds { start: String },
+    /// Every walk reaches an end node, but never all of them at the same step.
+    NeverTogether,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NoStartNodes => write!(f, "No node matches the start nodes"),
+            SolveError::NeverEnds { start } => {
+                write!(f, "The walk from {} never reaches an end node", start)
+            }
+            SolveError::NeverTogether => {
+                write!(f, "The walks never stand on end nodes at the same step")
+            }
+        }
+    }
+}
+
+/// Steps until every walk from a start node stands on an end node at once. When every walk
+/// is LCM compatible that is the least common multiple of their first ends, otherwise the end
+/// steps of the first walk are tried one by one.
+pub fn solve_walks(file: &str, start: &NodeMatcher, end: &NodeMatcher) -> Result<i64, SolveError> {
+    let (instructions, network) = parse(file);
+    let ends = network.mask(end);
+
+    let walks = network
+        .select(start)
+        .into_iter()
+        .map(|s| GhostWalk::new(s, &instructions, &network, &ends))
+        .collect::<Vec<_>>();
+    let Some(first) = walks.first() else {
+        return Err(SolveError::NoStartNodes);
+    };
+    if let Some(walk) = walks.iter().find(|w| w.end_steps.is_empty()) {
+        return Err(SolveError::NeverEnds {
+            start: network.name(walk.start).to_string(),
+        });
+    }
+
+    if walks.iter().all(|w| w.lcm_compatible()) {
+        return Ok(walks.iter().fold(1, |acc, w| {
+            least_common_multiple(acc, w.first_end().unwrap() as i64)
+        }));
+    }
+
+    // Once every walk is in its cycle they all repeat together after the LCM of the cycle
+    // lengths, so a step past that would have shown up earlier
+    let horizon = walks.iter().map(|w| w.cycle_start).max().unwrap() as i64
+        + walks.iter().fold(1, |acc, w| {
+            least_common_multiple(acc, w.cycle_length as i64)
+        });
+    let together = first
+        .end_times()
+        .take_while(|&steps| (steps as i64) < horizon)
+        .find(|&steps| walks[1..].iter().all(|w| w.is_on_end(steps)));
+    together
+        .map(|steps| steps as i64)
+        .ok_or(SolveError::NeverTogether)
+}
+
+/// A node name as a quoted DOT id.
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// The network as a Graphviz digraph, with start nodes in green and end nodes in red.
+pub fn to_dot(network: &Network, start: &NodeMatcher, end: &NodeMatcher) -> String {
+    let mut dot = String::from("digraph network {\n");
+    for id in network.sorted_ids() {
+        let name = network.name(id);
+        if start.matches(name) {
+            writeln!(dot, "  {} [style=filled, fillcolor=green];", dot_id(name)).unwrap();
+        } else if end.matches(name) {
+            writeln!(dot, "  {} [style=filled, fillcolor=red];", dot_id(name)).unwrap();
+        }
+    }
+    for id in network.sorted_ids() {
+        let name = dot_id(network.name(id));
+        let [left, right] = network.neighbours(id).map(|n| dot_id(network.name(n)));
+        if left == right {
+            writeln!(dot, "  {} -> {} [label=\"LR\"];", name, left).unwrap();
+        } else {
+            writeln!(dot, "  {} -> {} [label=\"L\"];", name, left).unwrap();
+            writeln!(dot, "  {} -> {} [label=\"R\"];", name, right).unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Strongly connected components with Tarjan's algorithm, each ordered by name, largest
+/// first.
+pub fn strongly_connected_components(network: &Network) -> Vec<Vec<u32>> {
+    let n = network.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low_link = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut components = Vec::new();
+
+    for root in network.sorted_ids() {
+        if index[root as usize] != usize::MAX {
+            continue;
+        }
+        // Explicit call stack of (node, next edge to look at)
+        let mut call_stack = vec![(root, 0)];
+        while let Some((node, edge)) = call_stack.pop() {
+            let i = node as usize;
+            if edge == 0 {
+                index[i] = next_index;
+                low_link[i] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[i] = true;
+            }
+            if let Some(&next) = network.neighbours(node).get(edge) {
+                call_stack.push((node, edge + 1));
+                if index[next as usize] == usize::MAX {
+                    call_stack.push((next, 0));
+                } else if on_stack[next as usize] {
+                    low_link[i] = low_link[i].min(index[next as usize]);
+                }
+                continue;
+            }
+
+            if low_link[i] == index[i] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member as usize] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_by_key(|&id| network.name(id));
+                components.push(component);
+            }
+            if let Some(&(parent, _)) = call_stack.last() {
+                let parent = parent as usize;
+                low_link[parent] = low_link[parent].min(low_link[i]);
+            }
+        }
+    }
+
+    components.sort_by(|a, b| {
+        b.len().cmp(&a.len()).then_with(|| {
+            a.iter()
+                .map(|&id| network.name(id))
+                .cmp(b.iter().map(|&id| network.name(id)))
+        })
+    });
+    components
+}
+
+/// How a single ghost moves when following the instructions from its start.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GhostWalk {
+    pub start: u32,
+    /// Every end node the ghost ever stands on.
+    pub ends: BTreeSet<u32>,
+    /// Steps before the (node, instruction) states start repeating.
+    pub cycle_start: usize,
+    pub cycle_length: usize,
+    /// Steps at which the ghost is on an end node, up to the end of the first cycle. After
+    /// that they repeat every `cycle_length` steps.
+    pub end_steps: Vec<usize>,
+}
+
+impl GhostWalk {
+    pub fn new(start: u32, instructions: &[Direction], network: &Network, ends: &[bool]) -> Self {
+        // First step at which each (node, instruction index) state was seen
+        let mut seen = vec![usize::MAX; network.len() * instructions.len()];
+        let mut path = Vec::new();
+        let mut current = start;
+        let mut steps = 0;
+        let cycle_start = loop {
+            let index = steps % instructions.len();
+            let state = current as usize * instructions.len() + index;
+            if seen[state] != usize::MAX {
+                break seen[state];
+            }
+            seen[state] = steps;
+            path.push(current);
+            current = network.next(current, instructions[index]);
+            steps += 1;
+        };
+
+        GhostWalk {
+            start,
+            ends: path
+                .iter()
+                .filter(|&&l| ends[l as usize])
+                .copied()
+                .collect(),
+            cycle_start,
+            cycle_length: steps - cycle_start,
+            end_steps: path
+                .iter()
+                .enumerate()
+                .filter(|(_, &l)| ends[l as usize])
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+
+    pub fn first_end(&self) -> Option<usize> {
+        self.end_steps.first().copied()
+    }
+
+    /// Whether the ghost stands on an end node after this many steps.
+    pub fn is_on_end(&self, steps: usize) -> bool {
+        let steps = match steps < self.cycle_start {
+            true => steps,
+            false => self.cycle_start + (steps - self.cycle_start) % self.cycle_length,
+        };
+        self.end_steps.binary_search(&steps).is_ok()
+    }
+
+    /// Every step count at which the ghost stands on an end node, in increasing order.
+    pub fn end_times(&self) -> impl Iterator<Item = usize> + '_ {
+        let (before_cycle, in_cycle): (Vec<_>, Vec<_>) =
+            self.end_steps.iter().partition(|&&s| s < self.cycle_start);
+        let repeats = match in_cycle.is_empty() {
+            true => 0,
+            false => usize::MAX,
+        };
+        before_cycle
+            .into_iter()
+            .chain((0..repeats).flat_map(move |k| {
+                let offset = k * self.cycle_length;
+                in_cycle.clone().into_iter().map(move |s| s + offset)
+            }))
+    }
+
+    /// Whether this ghost is on an end node after exactly every multiple of its first end
+    /// count, which is what taking the LCM of the first end counts relies on.
+    pub fn lcm_compatible(&self) -> bool {
+        match self.first_end() {
+            Some(first) if first > 0 && self.cycle_length.is_multiple_of(first) => self
+                .end_steps
+                .iter()
+                .copied()
+                .eq((first..self.cycle_start + self.cycle_length).step_by(first)),
+            _ => false,
+        }
+    }
+}
+
+/// A summary of the structure of the network, and whether the LCM shortcut holds.
+pub fn report(
+    instructions: &[Direction],
+    network: &Network,
+    start: &NodeMatcher,
+    end: &NodeMatcher,
+) -> String {
+    let names =
+        |ids: &mut dyn Iterator<Item = &u32>| ids.map(|&id| network.name(id)).collect::<Vec<_>>();
+
+    let mut out = String::new();
+    let components = strongly_connected_components(network);
+    let cyclic = components
+        .iter()
+        .filter(|c| c.len() > 1 || network.neighbours(c[0]).contains(&c[0]))
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "{} nodes, {} strongly connected components, {} of them cyclic",
+        network.len(),
+        components.len(),
+        cyclic.len()
+    )
+    .unwrap();
+    for component in cyclic {
+        writeln!(
+            out,
+            "  component of {}: {}",
+            component.len(),
+            names(&mut component.iter()).join(" ")
+        )
+        .unwrap();
+    }
+
+    let ends = network.mask(end);
+    let mut all_compatible = true;
+    for start in network.select(start) {
+        let walk = GhostWalk::new(start, instructions, network, &ends);
+        let compatible = walk.lcm_compatible();
+        all_compatible &= compatible;
+        writeln!(
+            out,
+            "{} reaches [{}], first end after {:?} steps (multiple of {} instructions: {}), cycle of {} steps from step {}, ends at steps {:?}, LCM compatible: {}",
+            network.name(start),
+            names(&mut walk.ends.iter()).join(", "),
+            walk.first_end(),
+            instructions.len(),
+            walk.first_end()
+                .is_some_and(|steps| steps > 0 && steps.is_multiple_of(instructions.len())),
+            walk.cycle_length,
+            walk.cycle_start,
+            walk.end_steps,
+            compatible
+        )
+        .unwrap();
+    }
+    writeln!(out, "LCM shortcut valid: {}", all_compatible).unwrap();
+    out
+}
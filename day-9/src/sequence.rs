@@ -0,0 +1,240 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceError {
+    Empty,
+    /// The differences ran out before reaching a row of only zeros, so the sequence is not
+    /// known to be a polynomial.
+    NoZeroRow {
+        rows: usize,
+    },
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::Empty => write!(f, "Empty sequence"),
+            SequenceError::NoZeroRow { rows } => write!(
+                f,
+                "Sequence does not reach a row of zeros within {} rows of differences",
+                rows
+            ),
+        }
+    }
+}
+
+/// An exact fraction, always stored with a positive denominator and in lowest terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    pub num: i128,
+    pub den: i128,
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Rational {
+    pub fn new(num: i128, den: i128) -> Self {
+        assert_ne!(den, 0, "Zero denominator");
+        let g = gcd(num, den).max(1) * den.signum();
+        Rational {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Rational { num: n, den: 1 }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+    fn add(self, other: Rational) -> Rational {
+        Rational::new(
+            self.num * other.den + other.num * self.den,
+            self.den * other.den,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+    fn sub(self, other: Rational) -> Rational {
+        Rational::new(
+            self.num * other.den - other.num * self.den,
+            self.den * other.den,
+        )
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+    fn mul(self, other: Rational) -> Rational {
+        Rational::new(self.num * other.num, self.den * other.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+    fn div(self, other: Rational) -> Rational {
+        Rational::new(self.num * other.den, self.den * other.num)
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.den {
+            1 => write!(f, "{}", self.num),
+            _ => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+/// The Newton forward difference table of a sequence, down to its first row of zeros.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferenceTable {
+    /// `rows[0]` is the sequence itself, every next row the differences of the one above.
+    pub rows: Vec<Vec<i64>>,
+}
+
+impl DifferenceTable {
+    pub fn new(seq: &[i64]) -> Result<Self, SequenceError> {
+        if seq.is_empty() {
+            return Err(SequenceError::Empty);
+        }
+
+        let mut rows = vec![seq.to_vec()];
+        while !rows.last().unwrap().iter().all(|d| *d == 0) {
+            let differences = rows
+                .last()
+                .unwrap()
+                .windows(2)
+                .map(|w| w[1] - w[0])
+                .collect::<Vec<i64>>();
+            if differences.is_empty() {
+                return Err(SequenceError::NoZeroRow { rows: rows.len() });
+            }
+            rows.push(differences);
+        }
+        Ok(DifferenceTable { rows })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows[0].is_empty()
+    }
+
+    /// Degree of the polynomial through the sequence, where all zeros counts as degree 0.
+    pub fn degree(&self) -> usize {
+        self.rows.len().saturating_sub(2)
+    }
+
+    /// The value at any index, where the sequence itself is at `0..len`.
+    ///
+    /// Uses Newton's forward formula `f(x) = sum_j diff_j * C(x, j)`, with `diff_j` the first
+    /// value of row `j`. `C(x, j)` stays an integer for negative `x` too.
+    pub fn value_at(&self, x: i64) -> i128 {
+        let x = x as i128;
+        let mut binomial = 1_i128;
+        let mut value = 0_i128;
+        for (j, row) in self.rows.iter().enumerate() {
+            value += row[0] as i128 * binomial;
+            binomial = binomial * (x - j as i128) / (j as i128 + 1);
+        }
+        value
+    }
+
+    /// The value `steps` places after the last one.
+    pub fn forward(&self, steps: i64) -> i128 {
+        self.value_at(self.len() as i64 - 1 + steps)
+    }
+
+    /// The value `steps` places before the first one.
+    pub fn backward(&self, steps: i64) -> i128 {
+        self.value_at(-steps)
+    }
+
+    /// Coefficients of the polynomial in the index, constant term first.
+    pub fn coefficients(&self) -> Vec<Rational> {
+        let mut coefficients = vec![Rational::from(0); self.degree() + 1];
+        // C(x, j) as a polynomial in x
+        let mut binomial = vec![Rational::from(1)];
+        for (j, row) in self.rows.iter().take(self.degree() + 1).enumerate() {
+            for (c, b) in coefficients.iter_mut().zip(binomial.iter()) {
+                *c = *c + *b * Rational::from(row[0] as i128);
+            }
+
+            // Multiply by (x - j) / (j + 1)
+            let scale = Rational::from(j as i128 + 1);
+            let mut next = vec![Rational::from(0); binomial.len() + 1];
+            for (i, b) in binomial.iter().enumerate() {
+                next[i + 1] = next[i + 1] + *b / scale;
+                next[i] = next[i] - *b * Rational::from(j as i128) / scale;
+            }
+            binomial = next;
+        }
+        coefficients
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degree_and_coefficients() {
+        // (x^2 + 3x + 2) / 2
+        let table = DifferenceTable::new(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(table.degree(), 2);
+        assert_eq!(
+            table.coefficients(),
+            vec![
+                Rational::new(1, 1),
+                Rational::new(3, 2),
+                Rational::new(1, 2)
+            ]
+        );
+        assert_eq!(table.coefficients()[1].to_string(), "3/2");
+
+        let table = DifferenceTable::new(&[5, 5, 5]).unwrap();
+        assert_eq!(table.degree(), 0);
+        assert_eq!(table.coefficients(), vec![Rational::from(5)]);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let table = DifferenceTable::new(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(table.forward(1), 68);
+        assert_eq!(table.backward(1), 5);
+        let values = (-3..10).map(|x| table.value_at(x)).collect::<Vec<_>>();
+        let again =
+            DifferenceTable::new(&values.iter().map(|&v| v as i64).collect::<Vec<_>>()).unwrap();
+        assert_eq!(again.forward(4), table.forward(8));
+        assert_eq!(again.backward(2), table.backward(5));
+    }
+
+    #[test]
+    fn test_no_zero_row() {
+        assert_eq!(
+            DifferenceTable::new(&[1, 2, 4, 8]),
+            Err(SequenceError::NoZeroRow { rows: 4 })
+        );
+        assert_eq!(DifferenceTable::new(&[]), Err(SequenceError::Empty));
+    }
+}